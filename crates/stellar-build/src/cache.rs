@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use crate::lock::{self, Error};
+
+/// Env var overriding the directory of the Wasm cache
+pub const CACHE_DIR_ENV: &str = "STELLAR_WASM_CACHE_DIR";
+
+/// Content addressed cache of downloaded Wasm binaries, stored as `<sha256>.wasm`
+/// in `~/.cache/stellar/wasm` by default
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The cache in `STELLAR_WASM_CACHE_DIR`, or else `$XDG_CACHE_HOME/stellar/wasm`
+    /// or `~/.cache/stellar/wasm`. `None` if no home directory can be found.
+    pub fn from_env() -> Option<Self> {
+        if let Ok(dir) = std::env::var(CACHE_DIR_ENV) {
+            return Some(Self::new(dir.into()));
+        }
+        let cache = std::env::var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|_| std::env::var("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .ok()?;
+        Some(Self::new(cache.join("stellar").join("wasm")))
+    }
+
+    pub fn path(&self, sha256: &str) -> PathBuf {
+        self.dir.join(sha256).with_extension("wasm")
+    }
+
+    /// Cached Wasm with the given hash. Entries which do not match their hash are ignored.
+    pub fn get(&self, sha256: &str) -> Option<Vec<u8>> {
+        let wasm = std::fs::read(self.path(sha256)).ok()?;
+        (lock::sha256(&wasm) == sha256).then_some(wasm)
+    }
+
+    pub fn insert(&self, wasm: &[u8]) -> Result<PathBuf, Error> {
+        let path = self.path(&lock::sha256(wasm));
+        std::fs::create_dir_all(&self.dir).map_err(|e| Error::Write(self.dir.clone(), e))?;
+        std::fs::write(&path, wasm).map_err(|e| Error::Write(path.clone(), e))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_is_content_addressed() {
+        let dir = std::env::temp_dir().join(format!("stellar-wasm-cache-{}", std::process::id()));
+        let cache = Cache::new(dir.clone());
+        let sha256 = lock::sha256(b"wasm");
+        assert_eq!(cache.get(&sha256), None);
+
+        let path = cache.insert(b"wasm").unwrap();
+        assert_eq!(path, dir.join(format!("{sha256}.wasm")));
+        assert_eq!(cache.get(&sha256), Some(b"wasm".to_vec()));
+
+        std::fs::write(&path, b"tampered").unwrap();
+        assert_eq!(cache.get(&sha256), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
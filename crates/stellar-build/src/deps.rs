@@ -1,12 +1,16 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     path::{Path, PathBuf},
-    process::Command,
 };
 
-use cargo_metadata::{Package, PackageId};
+use cargo_metadata::{DependencyKind, Package, PackageId};
 use topological_sort::TopologicalSort;
 
+use crate::Network;
+
+/// Environment variable overriding the target directory used by [`get_target_dir`]
+pub const TARGET_DIR_ENV: &str = "STELLAR_TARGET_DIR";
+
 /// Retrieves the target directory for a Cargo project and appends "stellar" to it.
 ///
 /// This function uses `cargo_metadata` to get the target directory of a Cargo project
@@ -16,6 +20,9 @@ use topological_sort::TopologicalSort;
 ///
 /// * `manifest_path` - A reference to a `Path` representing the location of the Cargo.toml file.
 ///
+/// `STELLAR_TARGET_DIR` takes precedence over the manifest's target directory, for builds
+/// which compile in a separate target directory but share the built Wasm.
+///
 /// # Returns
 ///
 /// Returns a `Result` containing:
@@ -29,6 +36,9 @@ use topological_sort::TopologicalSort;
 /// - There's an issue executing the metadata command.
 /// - Any other error occurs during the metadata retrieval process.
 pub fn get_target_dir(manifest_path: &Path) -> Result<PathBuf, cargo_metadata::Error> {
+    if let Some(target_dir) = std::env::var_os(TARGET_DIR_ENV) {
+        return Ok(stellar_wasm_out_dir(Path::new(&target_dir)));
+    }
     Ok(stellar_wasm_out_dir(
         cargo_metadata::MetadataCommand::new()
             .manifest_path(manifest_path)
@@ -60,18 +70,29 @@ impl PackageExt for Package {
 pub enum Error {
     #[error("Failed to find root package with manifest_path {0:?}")]
     RootNotFound(PathBuf),
-    #[error("Failed to cargo tree at manifest_path {0:?}")]
-    CargoTree(PathBuf),
+    #[error("Failed to resolve dependencies at manifest_path {0:?}")]
+    ResolveNotFound(PathBuf),
     #[error("Failed to get parent of {0}")]
     ParentNotFound(PathBuf),
     #[error(transparent)]
     Metadata(#[from] cargo_metadata::Error),
+    #[error(
+        "contract dependency cycle between {}: {}",
+        packages.join(", "),
+        edges.iter().map(|(from, to)| format!("{from} depends on {to}")).collect::<Vec<_>>().join(", ")
+    )]
+    DependencyCycle {
+        packages: Vec<String>,
+        /// Each edge is `(dependent, dependency)`
+        edges: Vec<(String, String)>,
+    },
 }
 
 /// Retrieves all dependencies for the given manifest path.
 ///
-/// This function executes `cargo tree` to get the dependency tree and processes the output
-/// to return a vector of `Package` structs representing all dependencies, including the root package.
+/// This function walks the `resolve` graph from `cargo metadata`, following normal
+/// dependencies for every target, and returns a vector of `Package` structs representing
+/// all dependencies, including the root package as the last element.
 ///
 /// # Arguments
 ///
@@ -86,63 +107,55 @@ pub enum Error {
 /// This function will return an error in the following situations:
 /// - If the metadata command fails to execute
 /// - If the root package is not found in the metadata
-/// - If the parent directory of the manifest path cannot be determined
-/// - If the `cargo tree` command fails to execute
-///
-/// # Panics
-///
-/// This function may panic in the following situations:
-/// - If the output of `cargo tree` contains invalid UTF-8 characters
-/// - If the parsing of package names and versions from the `cargo tree` output fails
+/// - If the metadata is missing its `resolve` graph
 pub fn all(manifest_path: &Path) -> Result<Vec<Package>, Error> {
     let metadata = cargo_metadata::MetadataCommand::new()
         .manifest_path(manifest_path)
         .exec()?;
 
-    let p = metadata
+    let root = metadata
         .root_package()
         .ok_or_else(|| Error::RootNotFound(manifest_path.to_path_buf()))?;
-
-    let packages = metadata
-        .packages
+    let nodes = metadata
+        .resolve
+        .as_ref()
+        .ok_or_else(|| Error::ResolveNotFound(manifest_path.to_path_buf()))?
+        .nodes
         .iter()
-        .map(|p| (format!("{}v{}", p.name, p.version), p))
-        .collect::<HashMap<String, &Package>>();
+        .map(|node| (&node.id, node))
+        .collect::<HashMap<_, _>>();
 
-    let parent = manifest_path
-        .parent()
-        .ok_or_else(|| Error::ParentNotFound(manifest_path.to_path_buf()))?;
-    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
-    let output = Command::new(cargo)
-        .current_dir(parent)
-        .args(["tree", "--prefix", "none", "--edges", "normal"])
-        .output()
-        .map_err(|_| Error::CargoTree(parent.to_path_buf()))?;
-    let stdout = output.stdout;
-    let stdout_str = String::from_utf8(stdout).unwrap();
-
-    let mut res = stdout_str
-        .lines()
-        .filter_map(|line| {
-            let s: Vec<&str> = line.split(' ').collect();
-            let package_id = format!("{}{}", s[0], s[1]);
-            let res = packages.get(&package_id).copied();
-            if let Some(r) = &res
-                && r == &p
-            {
-                return None;
+    let mut seen = HashSet::from([&root.id]);
+    let mut queue = VecDeque::from([&root.id]);
+    let mut res = Vec::new();
+    while let Some(id) = queue.pop_front() {
+        let deps = nodes.get(id).into_iter().flat_map(|node| &node.deps);
+        for dep in deps {
+            let normal = dep
+                .dep_kinds
+                .iter()
+                .any(|info| info.kind == DependencyKind::Normal);
+            if normal && seen.insert(&dep.pkg) {
+                queue.push_back(&dep.pkg);
+                if let Some(package) = metadata.packages.iter().find(|p| p.id == dep.pkg) {
+                    res.push(package.clone());
+                }
             }
-            res.cloned()
-        })
-        .collect::<Vec<_>>();
-    res.push(p.clone());
+        }
+    }
+    res.push(root.clone());
     Ok(res)
 }
 
+/// Directory of the Wasm built for the network from the environment, named after
+/// [`Network::name`], e.g. `target/stellar/testnet` or `target/stellar/custom-1a2b3c4d`
 #[must_use]
 pub fn stellar_wasm_out_dir(target_dir: &Path) -> PathBuf {
-    let network = std::env::var("STELLAR_NETWORK").unwrap_or_else(|_| "local".to_owned());
-    target_dir.join("stellar").join(network)
+    network_wasm_out_dir(target_dir, &Network::passphrase_from_env())
+}
+
+fn network_wasm_out_dir(target_dir: &Path, network: &Network) -> PathBuf {
+    target_dir.join("stellar").join(network.name())
 }
 
 #[must_use]
@@ -220,6 +233,28 @@ pub fn contract(manifest_path: &Path) -> Result<Vec<Package>, Error> {
         .collect())
 }
 
+/// Contract dependencies of each package, limited to those in `packages`.
+///
+/// # Errors
+///
+/// This function will return an Error if there's an issue retrieving contract
+/// dependencies for any of the packages.
+pub fn contract_dependencies(
+    packages: &[Package],
+) -> Result<BTreeMap<PackageId, Vec<PackageId>>, Error> {
+    packages
+        .iter()
+        .map(|p| {
+            let deps = contract(&p.manifest_path.clone().into_std_path_buf())?
+                .into_iter()
+                .filter(|dep| packages.iter().any(|p2| p2.id == dep.id))
+                .map(|dep| dep.id)
+                .collect();
+            Ok((p.id.clone(), deps))
+        })
+        .collect()
+}
+
 /// Constructs a workspace from a list of packages, sorting them topologically based on their contract dependencies.
 ///
 /// This function creates a dependency graph of the provided packages and their contract dependencies,
@@ -240,11 +275,23 @@ pub fn contract(manifest_path: &Path) -> Result<Vec<Package>, Error> {
 /// * There's an issue retrieving contract dependencies for any of the packages.
 /// * The dependency graph contains cycles, making topological sorting impossible.
 pub fn get_workspace(packages: &[Package]) -> Result<Vec<Package>, Error> {
+    sort_workspace(packages, &contract_dependencies(packages)?)
+}
+
+/// Sort packages topologically, given the contract dependencies of each package
+/// from [`contract_dependencies`]
+///
+/// # Errors
+///
+/// Returns [`Error::DependencyCycle`] if the contracts depend on each other cyclically.
+pub fn sort_workspace(
+    packages: &[Package],
+    dependencies: &BTreeMap<PackageId, Vec<PackageId>>,
+) -> Result<Vec<Package>, Error> {
     let mut graph: TopologicalSort<PackageId> = TopologicalSort::new();
     for p in packages {
-        let contract_deps = contract(&p.manifest_path.clone().into_std_path_buf())?;
-        for dep in contract_deps {
-            graph.add_dependency(dep.id.clone(), p.id.clone());
+        for dep in dependencies.get(&p.id).into_iter().flatten() {
+            graph.add_dependency(dep.clone(), p.id.clone());
         }
         graph.insert(p.id.clone());
     }
@@ -254,7 +301,55 @@ pub fn get_workspace(packages: &[Package]) -> Result<Vec<Package>, Error> {
             res.push(contract.clone());
         }
     }
-    Ok(res)
+    if graph.is_empty() {
+        return Ok(res);
+    }
+
+    let remaining = packages
+        .iter()
+        .filter(|p| !res.iter().any(|sorted| sorted.id == p.id))
+        .map(|p| p.id.clone())
+        .collect::<Vec<_>>();
+    let cycle = find_cycle(&remaining, dependencies);
+    let name = |id: &PackageId| {
+        packages
+            .iter()
+            .find(|p| &p.id == id)
+            .map_or_else(|| id.repr.clone(), |p| p.name.clone())
+    };
+    Err(Error::DependencyCycle {
+        packages: cycle.iter().map(name).collect(),
+        edges: cycle
+            .iter()
+            .zip(cycle.iter().cycle().skip(1))
+            .map(|(from, to)| (name(from), name(to)))
+            .collect(),
+    })
+}
+
+/// Find a cycle among `remaining`, the packages left over after a topological sort.
+///
+/// Every left over package depends on at least one other left over package, so following
+/// those dependencies from any of them must eventually revisit a package.
+fn find_cycle(
+    remaining: &[PackageId],
+    dependencies: &BTreeMap<PackageId, Vec<PackageId>>,
+) -> Vec<PackageId> {
+    let mut path: Vec<PackageId> = Vec::new();
+    let mut current = remaining.first().cloned();
+    while let Some(id) = current {
+        if let Some(start) = path.iter().position(|p| *p == id) {
+            return path.split_off(start);
+        }
+        current = dependencies
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .find(|dep| remaining.contains(dep))
+            .cloned();
+        path.push(id);
+    }
+    path
 }
 
 #[cfg(test)]
@@ -274,4 +369,86 @@ mod tests {
         let deps = all(&manifest_path).unwrap();
         println!("{deps:#?}\n{}", deps.len());
     }
+
+    #[test]
+    fn wasm_out_dir_is_named_after_the_network() {
+        let target = Path::new("target");
+        assert_eq!(
+            network_wasm_out_dir(target, &Network::Local),
+            Path::new("target/stellar/local")
+        );
+        assert_eq!(
+            network_wasm_out_dir(target, &Network::Testnet),
+            Path::new("target/stellar/testnet")
+        );
+        let custom = Network::from_any_passphrase("Private Testing Network ; October 2026");
+        assert_eq!(
+            network_wasm_out_dir(target, &custom),
+            target.join("stellar").join(custom.name())
+        );
+        assert!(custom.name().starts_with("custom-"));
+    }
+
+    fn fixture(contract: &str) -> PathBuf {
+        std::env::current_dir()
+            .unwrap()
+            .join("../stellar-scaffold-test/fixtures/contract-deps/contracts")
+            .join(contract)
+            .join("Cargo.toml")
+    }
+
+    fn names(packages: &[Package]) -> Vec<String> {
+        let mut names = packages.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn all_follows_normal_renamed_and_target_deps() {
+        let deps = all(&fixture("app")).unwrap();
+        assert_eq!(deps.last().unwrap().name, "app");
+        assert_eq!(names(&deps), ["app", "oracle", "token", "util"]);
+    }
+
+    #[test]
+    fn contract_deps_exclude_libraries_and_dev_deps() {
+        let deps = contract(&fixture("app")).unwrap();
+        assert_eq!(names(&deps), ["oracle", "token"]);
+        assert!(contract(&fixture("token")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn workspace_sorts_contracts_before_dependents() {
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(fixture("app"))
+            .exec()
+            .unwrap();
+        let packages = metadata
+            .workspace_packages()
+            .into_iter()
+            .filter(|p| p.is_dep())
+            .cloned()
+            .collect::<Vec<_>>();
+        let sorted = get_workspace(&packages).unwrap();
+        assert_eq!(sorted.len(), 4);
+        assert_eq!(sorted.last().unwrap().name, "app");
+    }
+
+    #[test]
+    fn find_cycle_skips_packages_depending_on_the_cycle() {
+        let id = |name: &str| PackageId {
+            repr: name.to_string(),
+        };
+        let dependencies = BTreeMap::from([
+            (id("app"), vec![id("a")]),
+            (id("a"), vec![id("b")]),
+            (id("b"), vec![id("c")]),
+            (id("c"), vec![id("a")]),
+        ]);
+        let remaining = [id("app"), id("a"), id("b"), id("c")];
+        assert_eq!(
+            find_cycle(&remaining, &dependencies),
+            vec![id("a"), id("b"), id("c")]
+        );
+    }
 }
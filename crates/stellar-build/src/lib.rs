@@ -1,4 +1,6 @@
+pub mod cache;
 pub mod deps;
+pub mod lock;
 pub mod networks;
 
 pub use deps::*;
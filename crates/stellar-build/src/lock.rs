@@ -0,0 +1,304 @@
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::Network;
+
+/// Name of the lockfile, kept at the workspace root next to `Cargo.lock`
+pub const LOCKFILE: &str = "stellar-registry.lock";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to read {0:?}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("Failed to write {0:?}: {1}")]
+    Write(PathBuf, std::io::Error),
+    #[error("Failed to parse {0:?}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+    #[error(transparent)]
+    Metadata(#[from] cargo_metadata::Error),
+    #[error(
+        "Hash mismatch for `{name}@{version}` on {network}:\n  locked:     {expected}\n  downloaded: {actual}\nRun `stellar registry lock --update {name}` if the new Wasm is expected"
+    )]
+    HashMismatch {
+        name: String,
+        network: String,
+        version: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Pins of Wasm binaries imported from a registry with `import_contract_client!`,
+/// and of named contracts imported with `import_contract_id!`:
+///
+/// ```toml
+/// [[wasm]]
+/// name = "unverified/oracle"
+/// network = "testnet"
+/// registry = "CAAX..."
+/// version = "1.2.0"
+/// sha256 = "5d1c..."
+///
+/// [[contract]]
+/// name = "unverified/oracle"
+/// network = "testnet"
+/// registry = "CAAX..."
+/// contract_id = "CBQH..."
+/// sha256 = "5d1c..."
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Lockfile {
+    #[serde(default, rename = "wasm")]
+    pub entries: Vec<Entry>,
+    #[serde(default, rename = "contract", skip_serializing_if = "Vec::is_empty")]
+    pub contracts: Vec<ContractEntry>,
+}
+
+/// A Wasm binary resolved from a registry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Entry {
+    /// Name as imported, including any channel, e.g. `unverified/oracle`
+    pub name: String,
+    /// Network name, or passphrase for networks without a well known name
+    pub network: String,
+    /// Contract ID of the registry the Wasm was published to
+    pub registry: String,
+    pub version: String,
+    /// Hex encoded SHA-256 of the Wasm binary
+    pub sha256: String,
+}
+
+/// A named contract resolved from a registry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContractEntry {
+    /// Name as imported, including any channel, e.g. `unverified/oracle`
+    pub name: String,
+    /// Network name, or passphrase for networks without a well known name
+    pub network: String,
+    /// Contract ID of the registry the contract is registered with
+    pub registry: String,
+    pub contract_id: String,
+    /// Hex encoded SHA-256 of the contract's Wasm, used to generate its client
+    pub sha256: String,
+}
+
+impl Entry {
+    /// Check that `wasm` is the pinned binary
+    pub fn verify(&self, wasm: &[u8]) -> Result<(), Error> {
+        let actual = sha256(wasm);
+        if actual == self.sha256 {
+            Ok(())
+        } else {
+            Err(Error::HashMismatch {
+                name: self.name.clone(),
+                network: self.network.clone(),
+                version: self.version.clone(),
+                expected: self.sha256.clone(),
+                actual,
+            })
+        }
+    }
+}
+
+impl Lockfile {
+    /// Path of the lockfile in the workspace of `manifest_path`
+    pub fn path(manifest_path: &Path) -> Result<PathBuf, Error> {
+        Ok(cargo_metadata::MetadataCommand::new()
+            .manifest_path(manifest_path)
+            .no_deps()
+            .exec()?
+            .workspace_root
+            .into_std_path_buf()
+            .join(LOCKFILE))
+    }
+
+    /// Find the lockfile of the crate in `manifest_dir`, in that directory or one of its
+    /// parents up to the workspace root, without running `cargo metadata`
+    pub fn find(manifest_dir: &Path) -> Option<PathBuf> {
+        for dir in manifest_dir.ancestors() {
+            let path = dir.join(LOCKFILE);
+            if path.exists() {
+                return Some(path);
+            }
+            if is_workspace_root(dir) {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Load the lockfile at `path`, or an empty one if it does not exist yet
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| Error::Read(path.to_path_buf(), e))?;
+        toml::from_str(&contents).map_err(|e| Error::Parse(path.to_path_buf(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let contents = format!(
+            "# This file is generated by `stellar registry lock`.\n# It pins registry Wasm imported with `import_contract_client!`; commit it to version control.\n\n{}",
+            toml::to_string_pretty(self)?
+        );
+        std::fs::write(path, contents).map_err(|e| Error::Write(path.to_path_buf(), e))
+    }
+
+    /// Pin of `name` on the network with `passphrase`
+    pub fn get(&self, name: &str, passphrase: &str) -> Option<&Entry> {
+        let network = network_name(passphrase);
+        self.entries
+            .iter()
+            .find(|e| e.name == name && e.network == network)
+    }
+
+    /// Add or replace the pin of `entry.name` on `entry.network`
+    pub fn insert(&mut self, entry: Entry) {
+        self.entries
+            .retain(|e| !(e.name == entry.name && e.network == entry.network));
+        self.entries.push(entry);
+        self.entries
+            .sort_by(|a, b| (&a.name, &a.network).cmp(&(&b.name, &b.network)));
+    }
+
+    /// Pin of the named contract `name` on the network with `passphrase`
+    pub fn get_contract(&self, name: &str, passphrase: &str) -> Option<&ContractEntry> {
+        let network = network_name(passphrase);
+        self.contracts
+            .iter()
+            .find(|e| e.name == name && e.network == network)
+    }
+
+    /// Add or replace the pin of the named contract `entry.name` on `entry.network`
+    pub fn insert_contract(&mut self, entry: ContractEntry) {
+        self.contracts
+            .retain(|e| !(e.name == entry.name && e.network == entry.network));
+        self.contracts.push(entry);
+        self.contracts
+            .sort_by(|a, b| (&a.name, &a.network).cmp(&(&b.name, &b.network)));
+    }
+}
+
+/// Whether `dir` has a `Cargo.toml` with a `[workspace]` table
+fn is_workspace_root(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+        .is_some_and(|manifest| manifest.contains_key("workspace"))
+}
+
+/// Hex encoded SHA-256 of a Wasm binary
+#[must_use]
+pub fn sha256(wasm: &[u8]) -> String {
+    Sha256::digest(wasm)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        })
+}
+
+/// Networks with a well known passphrase are named, e.g. `testnet`; others are
+/// identified by their passphrase
+#[must_use]
+pub fn network_name(passphrase: &str) -> String {
+    match Network::from_any_passphrase(passphrase) {
+        Network::Custom { passphrase } => passphrase,
+        network => network.name(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, network: &str, wasm: &[u8]) -> Entry {
+        Entry {
+            name: name.to_string(),
+            network: network.to_string(),
+            registry: "C".to_string(),
+            version: "1.0.0".to_string(),
+            sha256: sha256(wasm),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut lock = Lockfile::default();
+        lock.insert(entry("unverified/oracle", "testnet", b"v1"));
+        lock.insert(entry("registry", "testnet", b"v1"));
+        lock.insert(entry("unverified/oracle", "testnet", b"v2"));
+        assert_eq!(lock.entries.len(), 2);
+        assert_eq!(lock.entries[0].name, "registry");
+
+        let toml = toml::to_string_pretty(&lock).unwrap();
+        let lock: Lockfile = toml::from_str(&toml).unwrap();
+        let oracle = lock
+            .get("unverified/oracle", Network::Testnet.passphrase())
+            .unwrap();
+        assert!(oracle.verify(b"v2").is_ok());
+        assert!(matches!(
+            oracle.verify(b"v1"),
+            Err(Error::HashMismatch { .. })
+        ));
+        assert!(
+            lock.get("unverified/oracle", Network::Mainnet.passphrase())
+                .is_none()
+        );
+        assert!(!toml.contains("[[contract]]"));
+    }
+
+    #[test]
+    fn contracts_are_pinned_separately() {
+        let mut lock = Lockfile::default();
+        lock.insert(entry("unverified/oracle", "testnet", b"v1"));
+        lock.insert_contract(ContractEntry {
+            name: "unverified/oracle".to_string(),
+            network: "testnet".to_string(),
+            registry: "C".to_string(),
+            contract_id: "C1".to_string(),
+            sha256: sha256(b"v1"),
+        });
+        let lock: Lockfile = toml::from_str(&toml::to_string_pretty(&lock).unwrap()).unwrap();
+        let testnet = Network::Testnet.passphrase();
+        assert_eq!(
+            lock.get_contract("unverified/oracle", testnet)
+                .unwrap()
+                .contract_id,
+            "C1"
+        );
+        assert_eq!(
+            lock.get("unverified/oracle", testnet).unwrap().version,
+            "1.0.0"
+        );
+        assert!(
+            lock.get_contract("unverified/oracle", Network::Local.passphrase())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn find_stops_at_workspace_root() {
+        let root = std::env::temp_dir().join(format!("stellar-lock-find-{}", std::process::id()));
+        let member = root.join("workspace").join("contracts").join("a");
+        std::fs::create_dir_all(&member).unwrap();
+        std::fs::write(root.join(LOCKFILE), "").unwrap();
+        assert_eq!(Lockfile::find(&member), Some(root.join(LOCKFILE)));
+
+        let workspace = root.join("workspace");
+        std::fs::write(workspace.join("Cargo.toml"), "[workspace]\nmembers = []\n").unwrap();
+        assert_eq!(Lockfile::find(&member), None);
+
+        std::fs::write(workspace.join(LOCKFILE), "").unwrap();
+        assert_eq!(Lockfile::find(&member), Some(workspace.join(LOCKFILE)));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
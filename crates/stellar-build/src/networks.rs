@@ -1,18 +1,24 @@
 use sha2::{Digest, Sha256};
 use std::str::FromStr;
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub enum Network {
     #[default]
     Local,
     Testnet,
     Futurenet,
     Mainnet,
+    /// Any other network, e.g. a private network or a fork, identified by its passphrase
+    Custom {
+        passphrase: String,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("Invalid STELLAR_NETWORK: {0}. Expected: local, testnet, futurenet, or mainnet.")]
+    #[error(
+        "Invalid STELLAR_NETWORK: {0}. Expected: local, testnet, futurenet, or mainnet. Set STELLAR_NETWORK_PASSPHRASE for other networks."
+    )]
     InvalidNetwork(String),
     #[error(
         r#"Invalid STELLAR_PASSPHRASE: {0}. 
@@ -47,13 +53,14 @@ impl Network {
             .unwrap_or_default()
     }
 
+    /// The network with passphrase `STELLAR_NETWORK_PASSPHRASE`, or else the one named by
+    /// `STELLAR_NETWORK`
     pub fn passphrase_from_env() -> Self {
         std::env::var("STELLAR_NETWORK_PASSPHRASE")
-            .ok()
-            .and_then(|s| Self::from_passphrase(&s).ok())
-            .unwrap_or_else(Self::from_env)
+            .map_or_else(|_| Self::from_env(), |s| Self::from_any_passphrase(&s))
     }
 
+    /// One of the well known networks; see [`Network::from_any_passphrase`] for others
     pub fn from_passphrase(passphrase: &str) -> Result<Self, Error> {
         Ok(match passphrase {
             "Standalone Network ; February 2017" => Network::Local,
@@ -64,12 +71,38 @@ impl Network {
         })
     }
 
+    /// The well known network with `passphrase`, or else a [`Network::Custom`] one
+    pub fn from_any_passphrase(passphrase: &str) -> Self {
+        Self::from_passphrase(passphrase).unwrap_or_else(|_| Network::Custom {
+            passphrase: passphrase.to_string(),
+        })
+    }
+
     pub fn passphrase(&self) -> &str {
         match self {
             Network::Local => "Standalone Network ; February 2017",
             Network::Testnet => "Test SDF Network ; September 2015",
             Network::Futurenet => "Test SDF Future Network ; October 2022",
             Network::Mainnet => "Public Global Stellar Network ; September 2015",
+            Network::Custom { passphrase } => passphrase,
+        }
+    }
+
+    /// Name of the network, usable as a directory name. Custom networks are named
+    /// `custom-` followed by the start of their network ID, e.g. `custom-1a2b3c4d`.
+    pub fn name(&self) -> String {
+        match self {
+            Network::Local => "local".to_string(),
+            Network::Testnet => "testnet".to_string(),
+            Network::Futurenet => "futurenet".to_string(),
+            Network::Mainnet => "mainnet".to_string(),
+            Network::Custom { .. } => {
+                let id = self.id();
+                format!(
+                    "custom-{:02x}{:02x}{:02x}{:02x}",
+                    id[0], id[1], id[2], id[3]
+                )
+            }
         }
     }
 
@@ -78,3 +111,33 @@ impl Network {
         Sha256::digest(self.passphrase().as_bytes()).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_networks() {
+        let passphrase = "Private Testing Network ; October 2026";
+        let network = Network::from_any_passphrase(passphrase);
+        assert_eq!(
+            network,
+            Network::Custom {
+                passphrase: passphrase.to_string()
+            }
+        );
+        assert_eq!(network.passphrase(), passphrase);
+        assert_eq!(network.id(), <[u8; 32]>::from(Sha256::digest(passphrase)));
+        assert!(network.name().starts_with("custom-"));
+        assert_eq!(network.name().len(), "custom-".len() + 8);
+
+        assert!(matches!(
+            Network::from_passphrase(passphrase),
+            Err(Error::InvalidNetworkPassphrase(_))
+        ));
+
+        let testnet = Network::from_any_passphrase(Network::Testnet.passphrase());
+        assert_eq!(testnet, Network::Testnet);
+        assert_eq!(testnet.name(), "testnet");
+    }
+}
@@ -0,0 +1,378 @@
+use soroban_rpc as rpc;
+use stellar_cli::{
+    assembled::simulate_and_assemble_transaction,
+    xdr::{
+        AccountId, ContractId, Hash, HostFunction, InvokeContractArgs, InvokeHostFunctionOp, Memo,
+        MuxedAccount, Operation, OperationBody, Preconditions, PublicKey, ScAddress, ScBytes,
+        ScString, ScSymbol, ScVal, SequenceNumber, Transaction, TransactionExt, Uint256, VecM,
+    },
+};
+
+use crate::{Error, RegistryError, contract::Contract, registry::Registry};
+
+/// Typed client for a registry contract.
+///
+/// Unlike [`Contract::invoke`], arguments are passed as XDR values rather than
+/// CLI-style argument vectors, and failures raised by the contract are returned
+/// as [`Error::Contract`].
+pub struct RegistryClient {
+    contract: Contract,
+}
+
+/// Account used as the source of read-only simulations
+const SIMULATION_ACCOUNT: [u8; 32] = [0; 32];
+
+/// Base fee used for registry transactions; resource fees are added by simulation
+const BASE_FEE: u32 = 100;
+
+/// An account or contract address, as accepted by the registry contract
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Address {
+    Account(stellar_strkey::ed25519::PublicKey),
+    Contract(stellar_strkey::Contract),
+}
+
+impl From<Address> for ScAddress {
+    fn from(value: Address) -> Self {
+        match value {
+            Address::Account(key) => {
+                ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key.0))))
+            }
+            Address::Contract(id) => ScAddress::Contract(ContractId(Hash(id.0))),
+        }
+    }
+}
+
+impl TryFrom<ScVal> for Address {
+    type Error = Error;
+
+    fn try_from(value: ScVal) -> Result<Self, Self::Error> {
+        match value {
+            ScVal::Address(ScAddress::Contract(ContractId(Hash(id)))) => {
+                Ok(Address::Contract(stellar_strkey::Contract(id)))
+            }
+            ScVal::Address(ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(
+                Uint256(key),
+            )))) => Ok(Address::Account(stellar_strkey::ed25519::PublicKey(key))),
+            other => Err(Error::InvalidReturnValue(format!(
+                "{other:?} is not an address"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Address::Account(key) => write!(f, "{key}"),
+            Address::Contract(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+/// Arguments for [`RegistryClient::deploy`]
+#[derive(Clone, Debug)]
+pub struct DeployArgs {
+    pub wasm_name: String,
+    pub version: Option<String>,
+    pub contract_name: String,
+    pub admin: Address,
+    /// Constructor arguments, if the contract has a constructor
+    pub init: Option<Vec<ScVal>>,
+    pub deployer: Option<Address>,
+}
+
+impl RegistryClient {
+    pub fn new(contract: Contract) -> Self {
+        Self { contract }
+    }
+
+    pub fn as_contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    /// Hash of a published Wasm; the latest version if `version` is `None`
+    pub async fn fetch_hash(
+        &self,
+        wasm_name: &str,
+        version: Option<&str>,
+    ) -> Result<[u8; 32], Error> {
+        let res = self
+            .view(
+                "fetch_hash",
+                vec![string(wasm_name)?, option_string(version)?],
+            )
+            .await?;
+        hash(res)
+    }
+
+    /// Most recent published version of a Wasm
+    pub async fn current_version(&self, wasm_name: &str) -> Result<String, Error> {
+        let res = self
+            .view("current_version", vec![string(wasm_name)?])
+            .await?;
+        to_string(res)
+    }
+
+    pub async fn fetch_contract_id(
+        &self,
+        contract_name: &str,
+    ) -> Result<stellar_strkey::Contract, Error> {
+        contract_id(
+            self.view("fetch_contract_id", vec![string(contract_name)?])
+                .await?,
+        )
+    }
+
+    pub async fn fetch_contract_owner(&self, contract_name: &str) -> Result<Address, Error> {
+        self.view("fetch_contract_owner", vec![string(contract_name)?])
+            .await?
+            .try_into()
+    }
+
+    /// Upload and publish a Wasm binary under `wasm_name` at `version`
+    pub async fn publish(
+        &self,
+        wasm_name: &str,
+        author: Address,
+        wasm: &[u8],
+        version: &str,
+    ) -> Result<(), Error> {
+        self.send(
+            "publish",
+            vec![
+                string(wasm_name)?,
+                ScVal::Address(author.into()),
+                ScVal::Bytes(ScBytes(wasm.to_vec().try_into()?)),
+                string(version)?,
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Publish the hash of an already uploaded Wasm binary
+    pub async fn publish_hash(
+        &self,
+        wasm_name: &str,
+        author: Address,
+        wasm_hash: [u8; 32],
+        version: &str,
+    ) -> Result<(), Error> {
+        self.send(
+            "publish_hash",
+            vec![
+                string(wasm_name)?,
+                ScVal::Address(author.into()),
+                ScVal::Bytes(ScBytes(wasm_hash.to_vec().try_into()?)),
+                string(version)?,
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Deploy a published Wasm and register the new contract under `contract_name`
+    pub async fn deploy(&self, args: DeployArgs) -> Result<stellar_strkey::Contract, Error> {
+        let DeployArgs {
+            wasm_name,
+            version,
+            contract_name,
+            admin,
+            init,
+            deployer,
+        } = args;
+        let init = match init {
+            Some(init) => ScVal::Vec(Some(init.try_into()?)),
+            None => ScVal::Void,
+        };
+        let res = self
+            .send(
+                "deploy",
+                vec![
+                    string(&wasm_name)?,
+                    option_string(version.as_deref())?,
+                    string(&contract_name)?,
+                    ScVal::Address(admin.into()),
+                    init,
+                    deployer.map_or(ScVal::Void, |d| ScVal::Address(d.into())),
+                ],
+            )
+            .await?;
+        contract_id(res)
+    }
+
+    /// Upgrade a named contract to a published Wasm, calling `upgrade_fn` (default `upgrade`)
+    pub async fn upgrade_contract(
+        &self,
+        contract_name: &str,
+        wasm_name: &str,
+        version: Option<&str>,
+        upgrade_fn: Option<&str>,
+    ) -> Result<stellar_strkey::Contract, Error> {
+        let upgrade_fn = match upgrade_fn {
+            Some(f) => ScVal::Symbol(ScSymbol(f.try_into()?)),
+            None => ScVal::Void,
+        };
+        let res = self
+            .send(
+                "upgrade_contract",
+                vec![
+                    string(contract_name)?,
+                    string(wasm_name)?,
+                    option_string(version)?,
+                    upgrade_fn,
+                ],
+            )
+            .await?;
+        contract_id(res)
+    }
+
+    /// Simulate a call and return its result without submitting a transaction
+    pub async fn view(&self, function: &str, args: Vec<ScVal>) -> Result<ScVal, Error> {
+        let client = self.contract.rpc_client()?;
+        let tx = self.build_tx(function, args, SIMULATION_ACCOUNT, 0)?;
+        let assembled = simulate_and_assemble_transaction(&client, &tx, None, None)
+            .await
+            .map_err(Error::from_rpc)?;
+        let result = assembled
+            .sim_response()
+            .results()?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::InvalidReturnValue(format!("{function} returned no result")))?;
+        Ok(result.xdr)
+    }
+
+    /// Sign and submit a call with the configured source account, returning its result
+    pub async fn send(&self, function: &str, args: Vec<ScVal>) -> Result<ScVal, Error> {
+        let config = self.contract.config();
+        let client = self.contract.rpc_client()?;
+        let key = config.key_pair()?;
+        let source = key.verifying_key().to_bytes();
+        let account = client
+            .get_account(&stellar_strkey::ed25519::PublicKey(source).to_string())
+            .await?;
+        let sequence: i64 = account.seq_num.into();
+        let tx = self.build_tx(function, args, source, sequence + 1)?;
+        let assembled = simulate_and_assemble_transaction(&client, &tx, None, None)
+            .await
+            .map_err(Error::from_rpc)?;
+        let txn = assembled.transaction().clone();
+        Ok(client
+            .send_transaction_polling(&config.sign(txn, false).await?)
+            .await
+            .map_err(Error::from_rpc)?
+            .return_value()?)
+    }
+
+    fn build_tx(
+        &self,
+        function: &str,
+        args: Vec<ScVal>,
+        source: [u8; 32],
+        sequence: i64,
+    ) -> Result<Transaction, Error> {
+        let op = Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                    contract_address: self.contract.sc_address(),
+                    function_name: function.try_into()?,
+                    args: args.try_into()?,
+                }),
+                auth: VecM::default(),
+            }),
+        };
+        Ok(Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(source)),
+            fee: BASE_FEE,
+            seq_num: SequenceNumber(sequence),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![op].try_into()?,
+            ext: TransactionExt::V0,
+        })
+    }
+}
+
+impl From<Registry> for RegistryClient {
+    fn from(registry: Registry) -> Self {
+        Self::new(registry.into_contract())
+    }
+}
+
+fn string(s: &str) -> Result<ScVal, Error> {
+    Ok(ScVal::String(ScString(s.try_into()?)))
+}
+
+fn option_string(s: Option<&str>) -> Result<ScVal, Error> {
+    s.map_or(Ok(ScVal::Void), string)
+}
+
+fn to_string(val: ScVal) -> Result<String, Error> {
+    match val {
+        ScVal::String(ScString(s)) => Ok(s.to_utf8_string_lossy()),
+        other => Err(Error::InvalidReturnValue(format!(
+            "{other:?} is not a string"
+        ))),
+    }
+}
+
+fn contract_id(val: ScVal) -> Result<stellar_strkey::Contract, Error> {
+    match Address::try_from(val)? {
+        Address::Contract(id) => Ok(id),
+        Address::Account(key) => Err(Error::InvalidContractId(key.to_string())),
+    }
+}
+
+fn hash(val: ScVal) -> Result<[u8; 32], Error> {
+    match val {
+        ScVal::Bytes(ScBytes(bytes)) => bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::InvalidReturnValue(format!("{bytes:?} is not a 32 byte hash"))),
+        other => Err(Error::InvalidReturnValue(format!(
+            "{other:?} is not a hash"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_round_trip() {
+        let id = stellar_strkey::Contract([7; 32]);
+        let val = ScVal::Address(Address::Contract(id).into());
+        assert_eq!(Address::try_from(val).unwrap(), Address::Contract(id));
+        let key = stellar_strkey::ed25519::PublicKey([9; 32]);
+        let val = ScVal::Address(Address::Account(key).into());
+        assert_eq!(Address::try_from(val).unwrap(), Address::Account(key));
+    }
+
+    #[test]
+    fn decodes_return_values() {
+        assert_eq!(
+            to_string(string("0.0.3").unwrap()).unwrap(),
+            "0.0.3".to_string()
+        );
+        let bytes = ScVal::Bytes(ScBytes([1; 32].to_vec().try_into().unwrap()));
+        assert_eq!(hash(bytes).unwrap(), [1; 32]);
+        assert!(matches!(
+            hash(ScVal::Void),
+            Err(Error::InvalidReturnValue(_))
+        ));
+    }
+
+    #[test]
+    fn maps_simulation_errors() {
+        let e =
+            rpc::Error::TransactionSimulationFailed("HostError: Error(Contract, #2)".to_string());
+        assert!(matches!(
+            Error::from_rpc(e),
+            Error::Contract(RegistryError::NoSuchVersion)
+        ));
+    }
+}
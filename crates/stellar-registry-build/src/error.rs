@@ -1,8 +1,12 @@
+use soroban_rpc as rpc;
 use stellar_cli::{
     commands::contract::invoke,
     config::{self, locator},
+    xdr,
 };
 
+use crate::RegistryError;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Invalid contract id: {0}")]
@@ -15,4 +19,52 @@ pub enum Error {
     Locator(#[from] locator::Error),
     #[error(transparent)]
     Build(#[from] stellar_build::networks::Error),
+    #[error("{0} (Error(Contract, #{code}))", code = .0.code())]
+    Contract(#[from] RegistryError),
+    #[error(transparent)]
+    Rpc(#[from] rpc::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error("Invalid return value: {0}")]
+    InvalidReturnValue(String),
+    #[error("Invalid registry event: {0}")]
+    InvalidEvent(String),
+    #[error("Failed to read or write event index {0:?}: {1}")]
+    IndexIo(std::path::PathBuf, std::io::Error),
+    #[error("Failed to read event index {path:?}: {error}")]
+    InvalidIndex {
+        path: std::path::PathBuf,
+        error: serde_json::Error,
+    },
+    #[error("Failed to read or write registry profiles at {0}: {1}")]
+    Profiles(std::path::PathBuf, String),
+    #[error("No registry profile named \"{0}\". See `stellar registry profile ls`")]
+    UnknownProfile(String),
+    #[error("Registry profile \"{profile}\" has no registry for network {network}")]
+    ProfileMissingNetwork { profile: String, network: String },
+    #[error(transparent)]
+    Network(#[from] config::network::Error),
+    #[error(transparent)]
+    Lock(#[from] stellar_build::lock::Error),
+    #[error("Invalid Wasm hash: {0}")]
+    InvalidHash(String),
+    #[error("Downloaded Wasm does not match hash {0}")]
+    WasmHashMismatch(String),
+    #[error("\"{0}\" is not a Wasm contract; import Stellar Asset Contracts with `import_asset!`")]
+    NotWasmContract(String),
+    #[error("\"{0}\" is not a contract ID, registry profile, or contract alias")]
+    UnknownRegistry(String),
+}
+
+impl Error {
+    /// Error of invoking a registry contract, as [`Error::Contract`] if the registry raised it
+    pub fn from_invoke(e: invoke::Error) -> Self {
+        RegistryError::from_message(&e.to_string()).map_or(Self::Invoke(e), Self::Contract)
+    }
+
+    /// Error of sending a transaction to a registry contract, as [`Error::Contract`] if the
+    /// registry raised it
+    pub fn from_rpc(e: rpc::Error) -> Self {
+        RegistryError::from_message(&e.to_string()).map_or(Self::Rpc(e), Self::Contract)
+    }
 }
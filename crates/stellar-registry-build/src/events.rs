@@ -0,0 +1,344 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use stellar_cli::xdr::{ScBytes, ScMap, ScString, ScSymbol, ScVal};
+
+use crate::{Error, client::Address};
+
+/// Events emitted by the registry contract.
+///
+/// Mirrors `contracts/registry/src/events.rs`. Addresses and hashes are kept as
+/// their strkey and hex encodings so the events can be stored and filtered as text.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RegistryEvent {
+    Register {
+        contract_name: String,
+        contract_id: String,
+        sac: bool,
+        wasm_hash: Option<String>,
+    },
+    Deploy {
+        wasm_name: String,
+        version: String,
+        deployer: String,
+        contract_id: String,
+        registry: String,
+    },
+    Publish {
+        wasm_name: String,
+        wasm_hash: String,
+        version: String,
+        author: String,
+    },
+    UpdateOwner {
+        contract_name: String,
+        new_owner: String,
+    },
+    UpdateAddress {
+        contract_name: String,
+        new_address: String,
+    },
+    Rename {
+        old_name: String,
+        new_name: String,
+    },
+    #[serde(rename = "security_flag")]
+    SecurityFlagContract {
+        flagged: bool,
+    },
+    #[serde(rename = "sub_reg")]
+    SubRegistry {
+        name: String,
+        contract_id: String,
+    },
+}
+
+impl RegistryEvent {
+    /// Topics of every event, as emitted by the contract
+    pub const TOPICS: [&'static str; 8] = [
+        "register",
+        "deploy",
+        "publish",
+        "update_owner",
+        "update_address",
+        "rename",
+        "security_flag",
+        "sub_reg",
+    ];
+
+    /// Decode an event from its topics and data. Returns `None` for events which
+    /// are not registry events, e.g. those emitted by the admin or upgrade interfaces.
+    pub fn decode(topics: &[ScVal], data: &ScVal) -> Result<Option<Self>, Error> {
+        let Some(ScVal::Symbol(ScSymbol(topic))) = topics.first() else {
+            return Ok(None);
+        };
+        let topic = topic.to_utf8_string_lossy();
+        if !Self::TOPICS.contains(&topic.as_str()) {
+            return Ok(None);
+        }
+        let fields = Fields::new(data)?;
+        Ok(Some(match topic.as_str() {
+            "register" => Self::Register {
+                contract_name: fields.string("contract_name")?,
+                contract_id: fields.address("contract_id")?,
+                sac: fields.bool("sac")?,
+                wasm_hash: fields.optional_hash("wasm_hash")?,
+            },
+            "deploy" => Self::Deploy {
+                wasm_name: fields.string("wasm_name")?,
+                version: fields.string("version")?,
+                deployer: fields.address("deployer")?,
+                contract_id: fields.address("contract_id")?,
+                registry: fields.address("registry")?,
+            },
+            "publish" => Self::Publish {
+                wasm_name: fields.string("wasm_name")?,
+                wasm_hash: fields.hash("wasm_hash")?,
+                version: fields.string("version")?,
+                author: fields.address("author")?,
+            },
+            "update_owner" => Self::UpdateOwner {
+                contract_name: fields.string("contract_name")?,
+                new_owner: fields.address("new_owner")?,
+            },
+            "update_address" => Self::UpdateAddress {
+                contract_name: fields.string("contract_name")?,
+                new_address: fields.address("new_address")?,
+            },
+            "rename" => Self::Rename {
+                old_name: fields.string("old_name")?,
+                new_name: fields.string("new_name")?,
+            },
+            "security_flag" => Self::SecurityFlagContract {
+                flagged: fields.bool("flagged")?,
+            },
+            "sub_reg" => Self::SubRegistry {
+                name: fields.string("name")?,
+                contract_id: fields.address("contract_id")?,
+            },
+            _ => return Ok(None),
+        }))
+    }
+
+    /// The event's topic, e.g. `publish`
+    pub fn topic(&self) -> &'static str {
+        match self {
+            Self::Register { .. } => "register",
+            Self::Deploy { .. } => "deploy",
+            Self::Publish { .. } => "publish",
+            Self::UpdateOwner { .. } => "update_owner",
+            Self::UpdateAddress { .. } => "update_address",
+            Self::Rename { .. } => "rename",
+            Self::SecurityFlagContract { .. } => "security_flag",
+            Self::SubRegistry { .. } => "sub_reg",
+        }
+    }
+
+    /// Contract and Wasm names mentioned by the event
+    pub fn names(&self) -> Vec<&str> {
+        match self {
+            Self::Register { contract_name, .. }
+            | Self::UpdateOwner { contract_name, .. }
+            | Self::UpdateAddress { contract_name, .. } => vec![contract_name.as_str()],
+            Self::Deploy { wasm_name, .. } | Self::Publish { wasm_name, .. } => {
+                vec![wasm_name.as_str()]
+            }
+            Self::Rename { old_name, new_name } => vec![old_name.as_str(), new_name.as_str()],
+            Self::SubRegistry { name, .. } => vec![name.as_str()],
+            Self::SecurityFlagContract { .. } => vec![],
+        }
+    }
+
+    /// Accounts which authored the event: publishers, deployers and new owners
+    pub fn authors(&self) -> Vec<&str> {
+        match self {
+            Self::Publish { author, .. } => vec![author.as_str()],
+            Self::Deploy { deployer, .. } => vec![deployer.as_str()],
+            Self::UpdateOwner { new_owner, .. } => vec![new_owner.as_str()],
+            _ => vec![],
+        }
+    }
+}
+
+impl Display for RegistryEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Register {
+                contract_name,
+                contract_id,
+                sac,
+                ..
+            } => write!(
+                f,
+                "{contract_name} -> {contract_id}{}",
+                if *sac { " (SAC)" } else { "" }
+            ),
+            Self::Deploy {
+                wasm_name,
+                version,
+                deployer,
+                contract_id,
+                ..
+            } => write!(f, "{wasm_name}@{version} -> {contract_id} by {deployer}"),
+            Self::Publish {
+                wasm_name,
+                wasm_hash,
+                version,
+                author,
+            } => write!(f, "{wasm_name}@{version} ({wasm_hash}) by {author}"),
+            Self::UpdateOwner {
+                contract_name,
+                new_owner,
+            } => write!(f, "{contract_name} owner -> {new_owner}"),
+            Self::UpdateAddress {
+                contract_name,
+                new_address,
+            } => write!(f, "{contract_name} address -> {new_address}"),
+            Self::Rename { old_name, new_name } => write!(f, "{old_name} -> {new_name}"),
+            Self::SecurityFlagContract { flagged } => write!(f, "flagged: {flagged}"),
+            Self::SubRegistry { name, contract_id } => write!(f, "{name} -> {contract_id}"),
+        }
+    }
+}
+
+/// Event data; `#[contractevent]` encodes fields as a map keyed by field name
+struct Fields<'a>(&'a ScMap);
+
+impl<'a> Fields<'a> {
+    fn new(data: &'a ScVal) -> Result<Self, Error> {
+        match data {
+            ScVal::Map(Some(map)) => Ok(Self(map)),
+            other => Err(Error::InvalidEvent(format!("{other:?} is not a map"))),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<&'a ScVal, Error> {
+        self.0
+            .iter()
+            .find(|entry| matches!(&entry.key, ScVal::Symbol(ScSymbol(s)) if s.as_slice() == key.as_bytes()))
+            .map(|entry| &entry.val)
+            .ok_or_else(|| Error::InvalidEvent(format!("missing field {key}")))
+    }
+
+    fn string(&self, key: &str) -> Result<String, Error> {
+        match self.get(key)? {
+            ScVal::String(ScString(s)) => Ok(s.to_utf8_string_lossy()),
+            other => Err(Error::InvalidEvent(format!(
+                "{key}: {other:?} is not a string"
+            ))),
+        }
+    }
+
+    fn bool(&self, key: &str) -> Result<bool, Error> {
+        match self.get(key)? {
+            ScVal::Bool(b) => Ok(*b),
+            other => Err(Error::InvalidEvent(format!(
+                "{key}: {other:?} is not a bool"
+            ))),
+        }
+    }
+
+    fn address(&self, key: &str) -> Result<String, Error> {
+        Ok(Address::try_from(self.get(key)?.clone())
+            .map_err(|_| Error::InvalidEvent(format!("{key} is not an address")))?
+            .to_string())
+    }
+
+    fn hash(&self, key: &str) -> Result<String, Error> {
+        match self.get(key)? {
+            ScVal::Bytes(ScBytes(bytes)) => Ok(hex::encode(bytes.as_slice())),
+            other => Err(Error::InvalidEvent(format!(
+                "{key}: {other:?} is not a hash"
+            ))),
+        }
+    }
+
+    fn optional_hash(&self, key: &str) -> Result<Option<String>, Error> {
+        match self.get(key)? {
+            ScVal::Void => Ok(None),
+            _ => self.hash(key).map(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_cli::xdr::ScMapEntry;
+
+    use super::*;
+
+    fn symbol(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    fn string(s: &str) -> ScVal {
+        ScVal::String(ScString(s.try_into().unwrap()))
+    }
+
+    fn map(fields: Vec<(&str, ScVal)>) -> ScVal {
+        ScVal::Map(Some(
+            fields
+                .into_iter()
+                .map(|(key, val)| ScMapEntry {
+                    key: symbol(key),
+                    val,
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        ))
+    }
+
+    #[test]
+    fn decodes_publish() {
+        let author = stellar_strkey::ed25519::PublicKey([1; 32]);
+        let data = map(vec![
+            ("author", ScVal::Address(Address::Account(author).into())),
+            ("version", string("0.0.2")),
+            (
+                "wasm_hash",
+                ScVal::Bytes(ScBytes([0xab; 32].to_vec().try_into().unwrap())),
+            ),
+            ("wasm_name", string("hello")),
+        ]);
+        let event = RegistryEvent::decode(&[symbol("publish")], &data)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            event,
+            RegistryEvent::Publish {
+                wasm_name: "hello".to_string(),
+                wasm_hash: "ab".repeat(32),
+                version: "0.0.2".to_string(),
+                author: author.to_string(),
+            }
+        );
+        assert_eq!(event.names(), vec!["hello"]);
+        assert_eq!(event.authors(), vec![author.to_string().as_str()]);
+    }
+
+    #[test]
+    fn decodes_register_without_hash() {
+        let id = stellar_strkey::Contract([2; 32]);
+        let data = map(vec![
+            ("contract_id", ScVal::Address(Address::Contract(id).into())),
+            ("contract_name", string("token")),
+            ("sac", ScVal::Bool(true)),
+            ("wasm_hash", ScVal::Void),
+        ]);
+        let event = RegistryEvent::decode(&[symbol("register")], &data)
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.to_string(), format!("token -> {id} (SAC)"));
+    }
+
+    #[test]
+    fn skips_unknown_topics() {
+        assert_eq!(
+            RegistryEvent::decode(&[symbol("set_admin")], &ScVal::Void).unwrap(),
+            None
+        );
+        assert!(RegistryEvent::decode(&[symbol("rename")], &ScVal::Void).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use soroban_rpc as rpc;
+use stellar_cli::{
+    config,
+    xdr::{Limits, ReadXdr, ScVal},
+};
+
+use crate::{Error, events::RegistryEvent};
+
+/// Default path of the local event index of the registry `contract_id`, in `registry/history`
+/// in the config directory
+pub fn default_path(config: &config::Args, contract_id: &str) -> Result<PathBuf, Error> {
+    Ok(config
+        .locator
+        .config_dir()?
+        .join("registry")
+        .join("history")
+        .join(format!("{contract_id}.json")))
+}
+
+/// Where to resume reading events from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Start {
+    Ledger(u32),
+    /// Exclusive; the ID of the last event already read
+    Cursor(String),
+}
+
+/// A contract event as returned by `getEvents`, with its topics and value decoded from XDR
+#[derive(Debug, Clone)]
+pub struct RawEvent {
+    pub id: String,
+    pub ledger: u32,
+    pub ledger_closed_at: String,
+    pub topics: Vec<ScVal>,
+    pub value: ScVal,
+}
+
+/// One `getEvents` response
+#[derive(Debug, Clone)]
+pub struct Page {
+    pub events: Vec<RawEvent>,
+    /// Where the RPC stopped scanning, which can be past the last event and before the
+    /// latest ledger
+    pub cursor: Option<String>,
+    pub latest_ledger: u32,
+}
+
+/// Ledger of an event ID or cursor, whose first part is a TOID with the ledger in its
+/// upper 32 bits
+fn cursor_ledger(cursor: &str) -> Option<u32> {
+    let toid: u64 = cursor.split('-').next()?.parse().ok()?;
+    u32::try_from(toid >> 32).ok()
+}
+
+/// Source of contract events; the RPC server, or a stand-in in tests
+pub(crate) trait EventSource {
+    async fn oldest_ledger(&self) -> Result<u32, Error>;
+
+    async fn events(&self, contract_id: &str, start: Start, limit: usize) -> Result<Page, Error>;
+}
+
+impl EventSource for rpc::Client {
+    async fn oldest_ledger(&self) -> Result<u32, Error> {
+        Ok(self.get_health().await?.oldest_ledger)
+    }
+
+    async fn events(&self, contract_id: &str, start: Start, limit: usize) -> Result<Page, Error> {
+        let start = match start {
+            Start::Ledger(ledger) => rpc::EventStart::Ledger(ledger),
+            Start::Cursor(cursor) => rpc::EventStart::Cursor(cursor),
+        };
+        let res = self
+            .get_events(
+                start,
+                Some(rpc::EventType::Contract),
+                &[contract_id.to_string()],
+                &[],
+                Some(limit),
+            )
+            .await?;
+        let events = res
+            .events
+            .into_iter()
+            .map(|event| {
+                Ok(RawEvent {
+                    topics: event
+                        .topic
+                        .iter()
+                        .map(|topic| ScVal::from_xdr_base64(topic, Limits::none()))
+                        .collect::<Result<_, _>>()?,
+                    value: ScVal::from_xdr_base64(&event.value, Limits::none())?,
+                    id: event.id,
+                    ledger: event.ledger,
+                    ledger_closed_at: event.ledger_closed_at,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Page {
+            events,
+            cursor: Some(res.cursor).filter(|cursor| !cursor.is_empty()),
+            latest_ledger: res.latest_ledger,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedEvent {
+    pub id: String,
+    pub ledger: u32,
+    pub ledger_closed_at: String,
+    #[serde(flatten)]
+    pub event: RegistryEvent,
+}
+
+/// Local, append-only index of a registry's events
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Index {
+    /// ID of the last event read, so the next sync only fetches newer events
+    pub cursor: Option<String>,
+    pub events: Vec<IndexedEvent>,
+}
+
+impl Index {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read(path).map_err(|e| Error::IndexIo(path.to_path_buf(), e))?;
+        serde_json::from_slice(&contents).map_err(|error| Error::InvalidIndex {
+            path: path.to_path_buf(),
+            error,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| Error::IndexIo(parent.to_path_buf(), e))?;
+        }
+        let contents = serde_json::to_vec_pretty(self).map_err(|error| Error::InvalidIndex {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        std::fs::write(path, contents).map_err(|e| Error::IndexIo(path.to_path_buf(), e))
+    }
+
+    /// Fetch every event newer than the cursor from the RPC server, returning how many
+    /// registry events were added. Starts at `start_ledger` when there is no cursor yet,
+    /// defaulting to the oldest ledger retained by the RPC.
+    pub async fn sync(
+        &mut self,
+        client: &rpc::Client,
+        contract_id: &str,
+        start_ledger: Option<u32>,
+        page_size: usize,
+    ) -> Result<usize, Error> {
+        self.sync_from(client, contract_id, start_ledger, page_size)
+            .await
+    }
+
+    /// Versions of `wasm_name` published to the registry, with the hex encoded hash of
+    /// each, oldest first
+    pub fn published(&self, wasm_name: &str) -> Vec<(&str, &str)> {
+        self.events
+            .iter()
+            .filter_map(|e| match &e.event {
+                RegistryEvent::Publish {
+                    wasm_name: name,
+                    wasm_hash,
+                    version,
+                    ..
+                } if name == wasm_name => Some((version.as_str(), wasm_hash.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Fetch every event newer than the cursor from `source`, up to the latest ledger
+    pub(crate) async fn sync_from(
+        &mut self,
+        source: &impl EventSource,
+        contract_id: &str,
+        start_ledger: Option<u32>,
+        page_size: usize,
+    ) -> Result<usize, Error> {
+        let mut added = 0;
+        loop {
+            let start = if let Some(cursor) = &self.cursor {
+                Start::Cursor(cursor.clone())
+            } else if let Some(ledger) = start_ledger {
+                Start::Ledger(ledger)
+            } else {
+                Start::Ledger(source.oldest_ledger().await?)
+            };
+            let previous = self.cursor.clone();
+            let page = source.events(contract_id, start, page_size).await?;
+            let short = page.events.len() < page_size;
+            for raw in page.events {
+                self.cursor = Some(raw.id.clone());
+                if let Some(event) = RegistryEvent::decode(&raw.topics, &raw.value)? {
+                    self.events.push(IndexedEvent {
+                        id: raw.id,
+                        ledger: raw.ledger,
+                        ledger_closed_at: raw.ledger_closed_at,
+                        event,
+                    });
+                    added += 1;
+                }
+            }
+            if let Some(cursor) = page.cursor {
+                self.cursor = Some(cursor);
+            }
+            // A short page only means the RPC reached the end of its scan window, which
+            // may be before the latest ledger
+            let caught_up = self
+                .cursor
+                .as_deref()
+                .and_then(cursor_ledger)
+                .is_none_or(|ledger| ledger >= page.latest_ledger);
+            if short && (caught_up || self.cursor == previous) {
+                return Ok(added);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use stellar_cli::xdr::{ScMapEntry, ScString, ScSymbol};
+
+    use super::*;
+
+    /// Stand-in for the RPC server, serving events from memory
+    #[derive(Default)]
+    struct StandIn {
+        events: Vec<RawEvent>,
+        /// Ledgers scanned per request, like the RPC's scan window. Unlimited if `None`
+        window: Option<u32>,
+        latest_ledger: u32,
+        requests: RefCell<Vec<Start>>,
+    }
+
+    fn id(ledger: u32) -> String {
+        format!("{:019}-0000000000", u64::from(ledger) << 32)
+    }
+
+    impl EventSource for StandIn {
+        async fn oldest_ledger(&self) -> Result<u32, Error> {
+            Ok(1)
+        }
+
+        async fn events(
+            &self,
+            _contract_id: &str,
+            start: Start,
+            limit: usize,
+        ) -> Result<Page, Error> {
+            self.requests.borrow_mut().push(start.clone());
+            let first_ledger = match &start {
+                Start::Ledger(ledger) => *ledger,
+                Start::Cursor(cursor) => cursor_ledger(cursor).unwrap(),
+            };
+            let end = self
+                .window
+                .map_or(u32::MAX, |window| first_ledger + window)
+                .min(self.latest_ledger + 1);
+            let events: Vec<RawEvent> = self
+                .events
+                .iter()
+                .skip_while(|e| match &start {
+                    Start::Ledger(ledger) => e.ledger < *ledger,
+                    Start::Cursor(cursor) => e.id <= *cursor,
+                })
+                .take_while(|e| e.ledger < end)
+                .take(limit)
+                .cloned()
+                .collect();
+            // Like the RPC, a full page ends at its last event, a short one at the end of
+            // the scan window
+            let cursor = if events.len() == limit {
+                events.last().map(|e| e.id.clone())
+            } else {
+                Some(id(end - 1))
+            };
+            Ok(Page {
+                events,
+                cursor,
+                latest_ledger: self.latest_ledger,
+            })
+        }
+    }
+
+    fn symbol(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    fn string(s: &str) -> ScVal {
+        ScVal::String(ScString(s.try_into().unwrap()))
+    }
+
+    fn rename(ledger: u32, old_name: &str, new_name: &str) -> RawEvent {
+        RawEvent {
+            id: id(ledger),
+            ledger,
+            ledger_closed_at: "2026-01-01T00:00:00Z".to_string(),
+            topics: vec![symbol("rename")],
+            value: ScVal::Map(Some(
+                vec![
+                    ScMapEntry {
+                        key: symbol("new_name"),
+                        val: string(new_name),
+                    },
+                    ScMapEntry {
+                        key: symbol("old_name"),
+                        val: string(old_name),
+                    },
+                ]
+                .try_into()
+                .unwrap(),
+            )),
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_is_incremental() {
+        let mut source = StandIn {
+            events: vec![
+                rename(1, "a", "b"),
+                rename(2, "b", "c"),
+                rename(3, "c", "d"),
+            ],
+            latest_ledger: 3,
+            ..Default::default()
+        };
+        let mut index = Index::default();
+        assert_eq!(index.sync_from(&source, "C", None, 2).await.unwrap(), 3);
+        assert_eq!(index.cursor, Some(id(3)));
+        assert_eq!(
+            *source.requests.borrow(),
+            vec![Start::Ledger(1), Start::Cursor(id(2))]
+        );
+
+        source.events.push(rename(4, "d", "e"));
+        source.latest_ledger = 4;
+        source.requests.borrow_mut().clear();
+        assert_eq!(index.sync_from(&source, "C", None, 2).await.unwrap(), 1);
+        assert_eq!(*source.requests.borrow(), vec![Start::Cursor(id(3))]);
+        assert_eq!(index.events.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn sync_continues_past_short_pages() {
+        let source = StandIn {
+            events: vec![rename(2, "a", "b"), rename(25, "b", "c")],
+            window: Some(10),
+            latest_ledger: 30,
+            ..Default::default()
+        };
+        let mut index = Index::default();
+        assert_eq!(index.sync_from(&source, "C", None, 100).await.unwrap(), 2);
+        // The second scan window holds no events, yet the sync goes on to ledger 25 and
+        // then to the latest ledger
+        assert_eq!(source.requests.borrow().len(), 4);
+        assert_eq!(index.cursor, Some(id(30)));
+    }
+
+    #[tokio::test]
+    async fn index_round_trips() {
+        let source = StandIn {
+            events: vec![rename(5, "a", "b")],
+            latest_ledger: 5,
+            ..Default::default()
+        };
+        let mut index = Index::default();
+        index.sync_from(&source, "C", Some(5), 100).await.unwrap();
+        let dir = std::env::temp_dir().join(format!("registry-history-{}", std::process::id()));
+        let path = dir.join("C.json");
+        index.save(&path).unwrap();
+        let loaded = Index::load(&path).unwrap();
+        assert_eq!(loaded.cursor, index.cursor);
+        assert_eq!(loaded.events, index.events);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,6 +1,14 @@
+pub mod client;
 pub mod contract;
 pub mod error;
+pub mod events;
+pub mod history;
 pub mod named_registry;
+pub mod profile;
 pub mod registry;
+pub mod registry_error;
+pub mod wasm;
 
+pub use client::RegistryClient;
 pub use error::Error;
+pub use registry_error::RegistryError;
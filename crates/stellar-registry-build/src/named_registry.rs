@@ -39,6 +39,15 @@ impl PrefixedName {
     pub async fn registry(&self, config: &config::Args) -> Result<Registry, Error> {
         Registry::from_named_registry(config, self).await
     }
+
+    /// The registry of this name's channel, within the root registry `root`
+    pub async fn registry_in(
+        &self,
+        config: &config::Args,
+        root: Option<&str>,
+    ) -> Result<Registry, Error> {
+        Registry::new_in(config, root, self.channel.as_deref()).await
+    }
 }
 
 impl Display for PrefixedName {
@@ -0,0 +1,153 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use stellar_cli::config::{self, locator};
+
+use stellar_build::lock::network_name;
+
+use crate::Error;
+
+/// Env var naming the registry to use: a contract ID, a contract alias, or a profile name.
+/// Same as the `--registry` flag of `stellar registry`.
+pub const REGISTRY_ENV: &str = "STELLAR_REGISTRY";
+
+/// Named registries, stored in `registry.toml` in the stellar config directory:
+///
+/// ```toml
+/// default = "team-a"
+///
+/// [profiles.team-a]
+/// testnet = "CAAX..."
+/// local = "CA55..."
+/// ```
+///
+/// Profiles map a network (by name, e.g. `testnet`, or by passphrase) to the
+/// registry's contract ID on that network.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Profiles {
+    /// Profile used when no registry is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Profiles {
+    pub fn path(locator: &locator::Args) -> Result<PathBuf, Error> {
+        Ok(locator.config_dir()?.join("registry.toml"))
+    }
+
+    pub fn load(locator: &locator::Args) -> Result<Self, Error> {
+        let path = Self::path(locator)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| Error::Profiles(path.clone(), e.to_string()))?;
+        toml::from_str(&contents).map_err(|e| Error::Profiles(path, e.to_string()))
+    }
+
+    pub fn save(&self, locator: &locator::Args) -> Result<(), Error> {
+        let path = Self::path(locator)?;
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| Error::Profiles(path.clone(), e.to_string()))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::Profiles(path.clone(), e.to_string()))?;
+        }
+        std::fs::write(&path, contents).map_err(|e| Error::Profiles(path, e.to_string()))
+    }
+
+    /// Set the registry of `profile` on the network with `passphrase`
+    pub fn insert(
+        &mut self,
+        profile: &str,
+        passphrase: &str,
+        contract_id: stellar_strkey::Contract,
+    ) {
+        self.profiles
+            .entry(profile.to_string())
+            .or_default()
+            .insert(network_name(passphrase), contract_id.to_string());
+    }
+
+    /// Registry contract ID of `profile` on the network with `passphrase`
+    pub fn resolve(
+        &self,
+        profile: &str,
+        passphrase: &str,
+    ) -> Result<stellar_strkey::Contract, Error> {
+        let networks = self
+            .profiles
+            .get(profile)
+            .ok_or_else(|| Error::UnknownProfile(profile.to_string()))?;
+        let id = networks
+            .iter()
+            .find_map(|(network, id)| (passphrase_of(network) == passphrase).then_some(id))
+            .ok_or_else(|| Error::ProfileMissingNetwork {
+                profile: profile.to_string(),
+                network: network_name(passphrase),
+            })?;
+        id.parse().map_err(|_| Error::InvalidContractId(id.clone()))
+    }
+}
+
+/// Resolve a `--registry` value: a contract ID, a profile name, or a contract alias
+pub fn resolve_registry(
+    registry: &str,
+    config: &config::Args,
+) -> Result<stellar_strkey::Contract, Error> {
+    if let Ok(id) = registry.parse() {
+        return Ok(id);
+    }
+    let passphrase = config.get_network()?.network_passphrase;
+    let profiles = Profiles::load(&config.locator)?;
+    if profiles.profiles.contains_key(registry) {
+        return profiles.resolve(registry, &passphrase);
+    }
+    config
+        .locator
+        .get_contract_id(registry, &passphrase)?
+        .ok_or_else(|| Error::UnknownRegistry(registry.to_string()))
+}
+
+fn passphrase_of(network_key: &str) -> String {
+    network_key.parse::<stellar_build::Network>().map_or_else(
+        |_| network_key.to_string(),
+        |network| network.passphrase().to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_cli::config::network::passphrase::{LOCAL, TESTNET};
+
+    use super::*;
+
+    #[test]
+    fn resolves_per_network() {
+        let mut profiles = Profiles::default();
+        let testnet = stellar_strkey::Contract([1; 32]);
+        let custom = stellar_strkey::Contract([2; 32]);
+        profiles.insert("team", TESTNET, testnet);
+        profiles.insert("team", "My Private Network", custom);
+
+        let toml = toml::to_string_pretty(&profiles).unwrap();
+        assert!(toml.contains("testnet = "));
+        let profiles: Profiles = toml::from_str(&toml).unwrap();
+
+        assert_eq!(profiles.resolve("team", TESTNET).unwrap(), testnet);
+        assert_eq!(
+            profiles.resolve("team", "My Private Network").unwrap(),
+            custom
+        );
+        assert!(matches!(
+            profiles.resolve("team", LOCAL),
+            Err(Error::ProfileMissingNetwork { .. })
+        ));
+        assert!(matches!(
+            profiles.resolve("other", TESTNET),
+            Err(Error::UnknownProfile(_))
+        ));
+    }
+}
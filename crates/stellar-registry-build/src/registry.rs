@@ -1,9 +1,10 @@
-use stellar_cli::config;
+use stellar_cli::{commands::txn_result::TxnResult, config};
 
 use crate::{
-    Error,
+    Error, RegistryClient,
     contract::{Contract, PreHashContractID},
     named_registry::PrefixedName,
+    profile::{self, Profiles, REGISTRY_ENV},
 };
 
 pub struct Registry(Contract);
@@ -16,7 +17,16 @@ impl Registry {
         Self::new(config, name.channel.as_deref()).await
     }
     pub async fn new(config: &config::Args, name: Option<&str>) -> Result<Self, Error> {
-        let contract = Self::verified(config)?;
+        Self::new_in(config, None, name).await
+    }
+
+    /// Like [`Registry::new`], looking up `name` in the registry `root` (see [`Registry::root`])
+    pub async fn new_in(
+        config: &config::Args,
+        root: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<Self, Error> {
+        let contract = Self::root(config, root)?;
         Ok(if let Some(name) = name {
             if let Ok(contract_id) = name.parse() {
                 Registry(Contract::new(contract_id, config))
@@ -29,12 +39,7 @@ impl Registry {
     }
 
     pub async fn fetch_contract_id(&self, name: &str) -> Result<stellar_strkey::Contract, Error> {
-        let slop = ["fetch_contract_id", "--contract-name", name];
-        let contract_id = self.0.invoke_with_result(&slop, true).await?;
-        contract_id
-            .trim_matches('"')
-            .parse()
-            .map_err(|_| Error::InvalidContractId(contract_id))
+        self.client().fetch_contract_id(name).await
     }
 
     pub async fn fetch_contract(&self, name: &str) -> Result<Contract, Error> {
@@ -44,19 +49,62 @@ impl Registry {
         ))
     }
 
+    /// Invoke the registry contract, returning failures it raises as [`Error::Contract`]
+    pub async fn invoke(&self, slop: &[&str], view_only: bool) -> Result<TxnResult<String>, Error> {
+        self.0
+            .invoke(slop, view_only)
+            .await
+            .map_err(Error::from_invoke)
+    }
+
+    /// Like [`Registry::invoke`], returning the result of the invocation
+    pub async fn invoke_with_result(
+        &self,
+        slop: &[&str],
+        view_only: bool,
+    ) -> Result<String, Error> {
+        Ok(self.invoke(slop, view_only).await?.into_result().unwrap())
+    }
+
     pub fn as_contract(&self) -> &Contract {
         &self.0
     }
 
+    pub fn into_contract(self) -> Contract {
+        self.0
+    }
+
+    /// Typed client for calling the registry contract
+    pub fn client(&self) -> RegistryClient {
+        RegistryClient::new(Contract::new(self.0.id(), self.0.config()))
+    }
+
+    /// The root registry, see [`Registry::root`]
     pub fn verified(config: &config::Args) -> Result<Self, Error> {
-        Ok(Registry(Contract::new(
-            if let Ok(id) = std::env::var("STELLAR_REGISTRY_CONTRACT_ID") {
-                id.parse().map_err(|_| Error::InvalidContractId(id))?
-            } else {
-                verified_contract_id(&config.get_network()?.network_passphrase)
-            },
-            config,
-        )))
+        Self::root(config, None)
+    }
+
+    /// The root registry, resolved in order from:
+    /// - `registry` (e.g. `--registry`): a contract ID, profile name, or contract alias
+    /// - `STELLAR_REGISTRY`: a contract ID, profile name, or contract alias
+    /// - `STELLAR_REGISTRY_CONTRACT_ID`
+    /// - the default profile in `registry.toml`, which must have a registry for the current network
+    /// - the verified registry, if there is no default profile
+    pub fn root(config: &config::Args, registry: Option<&str>) -> Result<Self, Error> {
+        let from_env = std::env::var(REGISTRY_ENV).ok();
+        let id = if let Some(registry) = registry.or(from_env.as_deref()) {
+            profile::resolve_registry(registry, config)?
+        } else if let Ok(id) = std::env::var("STELLAR_REGISTRY_CONTRACT_ID") {
+            id.parse().map_err(|_| Error::InvalidContractId(id))?
+        } else {
+            let passphrase = config.get_network()?.network_passphrase;
+            let profiles = Profiles::load(&config.locator)?;
+            match profiles.default.as_deref() {
+                Some(default) => profiles.resolve(default, &passphrase)?,
+                None => verified_contract_id(&passphrase),
+            }
+        };
+        Ok(Registry(Contract::new(id, config)))
     }
 }
 
@@ -72,8 +120,9 @@ pub fn stellar_address() -> stellar_strkey::ed25519::PublicKey {
 }
 
 pub fn contract_id(network_passphrase: &str, salt: &str) -> stellar_strkey::Contract {
-    PreHashContractID::new(stellar_address(), salt)
-        .id(&stellar_build::Network::from_passphrase(network_passphrase).unwrap())
+    PreHashContractID::new(stellar_address(), salt).id(
+        &stellar_build::Network::from_any_passphrase(network_passphrase),
+    )
 }
 
 pub fn verified_contract_id(network_passphrase: &str) -> stellar_strkey::Contract {
@@ -122,4 +171,10 @@ mod generate_id {
             &expect!["CA55VGAFPIZHOY2X26KANRJYFBWPEXGNLIEHR7Q5TR2576HKHOFPLBTX"],
         );
     }
+
+    #[test]
+    fn custom() {
+        let id = super::verified_contract_id("Private Testing Network ; October 2026");
+        assert_ne!(id, super::verified_contract_id(LOCAL));
+    }
 }
@@ -0,0 +1,219 @@
+use std::fmt::Display;
+
+/// Errors returned by the registry contract.
+///
+/// Mirrors `contracts/registry/src/error.rs`; the discriminants are the codes
+/// reported by the host as `Error(Contract, #<code>)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum RegistryError {
+    NoSuchWasmPublished = 1,
+    NoSuchVersion = 2,
+    WasmNameAlreadyTaken = 3,
+    NoSuchContractDeployed = 4,
+    AlreadyDeployed = 5,
+    UpgradeInvokeFailed = 6,
+    AdminOnly = 7,
+    VersionMustBeGreaterThanCurrent = 8,
+    InvalidName = 9,
+    InvalidVersion = 10,
+    HashAlreadyPublished = 11,
+    ManagerRequired = 12,
+    NoPendingBatch = 13,
+    NotContractOwner = 14,
+    BatchEntryExpired = 15,
+    AccountAddressNotValid = 16,
+    ContractIdAddressDoesNotExist = 17,
+    ProxyInvocationFailed = 18,
+    ProxyContractCompromised = 19,
+    SubRegistryCrossContractCallFailed = 20,
+    SubRegistryIsSelf = 21,
+}
+
+impl RegistryError {
+    pub const ALL: [Self; 21] = [
+        Self::NoSuchWasmPublished,
+        Self::NoSuchVersion,
+        Self::WasmNameAlreadyTaken,
+        Self::NoSuchContractDeployed,
+        Self::AlreadyDeployed,
+        Self::UpgradeInvokeFailed,
+        Self::AdminOnly,
+        Self::VersionMustBeGreaterThanCurrent,
+        Self::InvalidName,
+        Self::InvalidVersion,
+        Self::HashAlreadyPublished,
+        Self::ManagerRequired,
+        Self::NoPendingBatch,
+        Self::NotContractOwner,
+        Self::BatchEntryExpired,
+        Self::AccountAddressNotValid,
+        Self::ContractIdAddressDoesNotExist,
+        Self::ProxyInvocationFailed,
+        Self::ProxyContractCompromised,
+        Self::SubRegistryCrossContractCallFailed,
+        Self::SubRegistryIsSelf,
+    ];
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.code() == code)
+    }
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// Find the first `Error(Contract, #<code>)` in a host or simulation error message
+    pub fn from_message(message: &str) -> Option<Self> {
+        const PREFIX: &str = "Error(Contract, #";
+        message.match_indices(PREFIX).find_map(|(start, _)| {
+            let rest = &message[start + PREFIX.len()..];
+            let (code, _) = rest.split_once(')')?;
+            Self::from_code(code.parse().ok()?)
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::NoSuchWasmPublished => "NoSuchWasmPublished",
+            Self::NoSuchVersion => "NoSuchVersion",
+            Self::WasmNameAlreadyTaken => "WasmNameAlreadyTaken",
+            Self::NoSuchContractDeployed => "NoSuchContractDeployed",
+            Self::AlreadyDeployed => "AlreadyDeployed",
+            Self::UpgradeInvokeFailed => "UpgradeInvokeFailed",
+            Self::AdminOnly => "AdminOnly",
+            Self::VersionMustBeGreaterThanCurrent => "VersionMustBeGreaterThanCurrent",
+            Self::InvalidName => "InvalidName",
+            Self::InvalidVersion => "InvalidVersion",
+            Self::HashAlreadyPublished => "HashAlreadyPublished",
+            Self::ManagerRequired => "ManagerRequired",
+            Self::NoPendingBatch => "NoPendingBatch",
+            Self::NotContractOwner => "NotContractOwner",
+            Self::BatchEntryExpired => "BatchEntryExpired",
+            Self::AccountAddressNotValid => "AccountAddressNotValid",
+            Self::ContractIdAddressDoesNotExist => "ContractIdAddressDoesNotExist",
+            Self::ProxyInvocationFailed => "ProxyInvocationFailed",
+            Self::ProxyContractCompromised => "ProxyContractCompromised",
+            Self::SubRegistryCrossContractCallFailed => "SubRegistryCrossContractCallFailed",
+            Self::SubRegistryIsSelf => "SubRegistryIsSelf",
+        }
+    }
+
+    /// A description of the error, as documented on the variant in the registry contract
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::NoSuchWasmPublished => "No Wasm has been published with this name",
+            Self::NoSuchVersion => "No such version of the contact has been published",
+            Self::WasmNameAlreadyTaken => "Wasm name already claimed",
+            Self::NoSuchContractDeployed => "No such contract deployed",
+            Self::AlreadyDeployed => "Contract already deployed",
+            Self::UpgradeInvokeFailed => "Failed to upgrade a contract",
+            Self::AdminOnly => "Only Admin is allowed",
+            Self::VersionMustBeGreaterThanCurrent => {
+                "New version must be greater than the most recent version"
+            }
+            Self::InvalidName => {
+                "Invalid name. Must be at most 64 characters and non-empty; \
+                 ascii alphanumeric, '-', or '_'; start with a ascii alphabetic character; \
+                 and not be a Rust keyword"
+            }
+            Self::InvalidVersion => "Must be valid cargo version",
+            Self::HashAlreadyPublished => "Hash has aleady been published",
+            Self::ManagerRequired => "Root registry requires manager when deploying",
+            Self::NoPendingBatch => "No pending batch entries to process",
+            Self::NotContractOwner => "Caller is not the contract owner",
+            Self::BatchEntryExpired => {
+                "Batch entry missing from temporary storage (likely expired)"
+            }
+            Self::AccountAddressNotValid => {
+                "Given \"contract ID\" appears to be a G-address, not a contract ID"
+            }
+            Self::ContractIdAddressDoesNotExist => {
+                "Given contract ID does not exist on this network"
+            }
+            Self::ProxyInvocationFailed => "Invoking contract's function has failed",
+            Self::ProxyContractCompromised => "Contract to be invoked is compromised",
+            Self::SubRegistryCrossContractCallFailed => "Subregistry contract call failed",
+            Self::SubRegistryIsSelf => {
+                "Subregistry must be a different contract than the current registry"
+            }
+        }
+    }
+
+    /// A generic suggestion for fixing the error, if there is one.
+    ///
+    /// Callers with more context (e.g. the current published version) should prefer
+    /// a more specific hint.
+    pub fn hint(self) -> Option<&'static str> {
+        Some(match self {
+            Self::NoSuchWasmPublished => {
+                "check the spelling and prefix of the Wasm name, or publish it first with `stellar registry publish`"
+            }
+            Self::NoSuchVersion => {
+                "check the published versions with `stellar registry current-version`"
+            }
+            Self::WasmNameAlreadyTaken | Self::AlreadyDeployed => {
+                "choose a different name, or use the `unverified/` prefix"
+            }
+            Self::NoSuchContractDeployed => {
+                "check the spelling and prefix of the contract name, or register it with `stellar registry register-contract`"
+            }
+            Self::VersionMustBeGreaterThanCurrent => {
+                "publish with a --binver greater than the current version"
+            }
+            Self::InvalidVersion => "use a semantic version such as --binver 0.1.0",
+            Self::HashAlreadyPublished => {
+                "this exact Wasm is already published; rebuild with your changes before publishing a new version"
+            }
+            Self::ManagerRequired => "pass a --deployer when deploying to the root registry",
+            Self::AdminOnly | Self::NotContractOwner => {
+                "sign with the owning account using --source"
+            }
+            Self::AccountAddressNotValid | Self::ContractIdAddressDoesNotExist => {
+                "pass the C... contract ID of a contract deployed on this network"
+            }
+            _ => return None,
+        })
+    }
+}
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name(), self.description())
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::RegistryError;
+
+    #[test]
+    fn codes_round_trip() {
+        for (i, error) in RegistryError::ALL.into_iter().enumerate() {
+            assert_eq!(error.code() as usize, i + 1);
+            assert_eq!(RegistryError::from_code(error.code()), Some(error));
+        }
+        assert_eq!(RegistryError::from_code(0), None);
+        assert_eq!(RegistryError::from_code(22), None);
+    }
+
+    #[test]
+    fn from_message() {
+        let message = "transaction simulation failed: HostError: Error(Contract, #8)\n\nEvent log:";
+        assert_eq!(
+            RegistryError::from_message(message),
+            Some(RegistryError::VersionMustBeGreaterThanCurrent)
+        );
+        assert_eq!(
+            RegistryError::from_message(message).unwrap().to_string(),
+            "VersionMustBeGreaterThanCurrent: New version must be greater than the most recent version"
+        );
+        assert_eq!(
+            RegistryError::from_message("Error(WasmVm, InvalidAction)"),
+            None
+        );
+        assert_eq!(RegistryError::from_message("Error(Contract, #99)"), None);
+    }
+}
@@ -0,0 +1,104 @@
+use stellar_build::lock::{self, ContractEntry, Entry};
+use stellar_cli::{
+    config,
+    utils::rpc::get_remote_wasm_from_hash,
+    xdr::{self, ContractExecutable, ScContractInstance, ScVal},
+};
+
+use crate::{Error, named_registry::PrefixedName};
+
+pub use stellar_build::cache::{CACHE_DIR_ENV, Cache};
+
+/// Resolve a published Wasm to a version and hash; the latest version if `version` is `None`
+pub async fn resolve(
+    config: &config::Args,
+    name: &str,
+    version: Option<&str>,
+) -> Result<Entry, Error> {
+    resolve_in(config, None, name, version).await
+}
+
+/// Like [`resolve`], within the root registry `root` (see [`crate::registry::Registry::root`])
+pub async fn resolve_in(
+    config: &config::Args,
+    root: Option<&str>,
+    name: &str,
+    version: Option<&str>,
+) -> Result<Entry, Error> {
+    let prefixed: PrefixedName = name.parse().expect("infallible");
+    let registry = prefixed.registry_in(config, root).await?;
+    let client = registry.client();
+    let version = match version {
+        Some(version) => version.to_string(),
+        None => client.current_version(&prefixed.name).await?,
+    };
+    // The registry stores the SHA-256 of each published Wasm
+    let hash = client.fetch_hash(&prefixed.name, Some(&version)).await?;
+    Ok(Entry {
+        name: name.to_string(),
+        network: lock::network_name(&config.get_network()?.network_passphrase),
+        registry: registry.as_contract().id().to_string(),
+        version,
+        sha256: hex::encode(hash),
+    })
+}
+
+/// Resolve a named contract to its contract ID and the hash of its Wasm
+pub async fn resolve_contract(config: &config::Args, name: &str) -> Result<ContractEntry, Error> {
+    resolve_contract_in(config, None, name).await
+}
+
+/// Like [`resolve_contract`], within the root registry `root` (see [`crate::registry::Registry::root`])
+pub async fn resolve_contract_in(
+    config: &config::Args,
+    root: Option<&str>,
+    name: &str,
+) -> Result<ContractEntry, Error> {
+    let prefixed: PrefixedName = name.parse().expect("infallible");
+    let registry = prefixed.registry_in(config, root).await?;
+    let contract_id = registry.client().fetch_contract_id(&prefixed.name).await?;
+    let network = config.get_network()?;
+    let instance = network
+        .rpc_client()?
+        .get_contract_data(&contract_id.0)
+        .await?;
+    let ScVal::ContractInstance(ScContractInstance {
+        executable: ContractExecutable::Wasm(hash),
+        ..
+    }) = instance.val
+    else {
+        return Err(Error::NotWasmContract(name.to_string()));
+    };
+    Ok(ContractEntry {
+        name: name.to_string(),
+        network: lock::network_name(&network.network_passphrase),
+        registry: registry.as_contract().id().to_string(),
+        contract_id: contract_id.to_string(),
+        sha256: hex::encode(hash.0),
+    })
+}
+
+/// Fetch the Wasm with hex encoded hash `sha256`, from the cache if present and otherwise
+/// from the network. Downloads are verified against the hash before being cached.
+pub async fn fetch(
+    config: &config::Args,
+    sha256: &str,
+    cache: Option<&Cache>,
+) -> Result<Vec<u8>, Error> {
+    if let Some(wasm) = cache.and_then(|cache| cache.get(sha256)) {
+        return Ok(wasm);
+    }
+    let hash = hex::decode(sha256)
+        .ok()
+        .and_then(|hash| <[u8; 32]>::try_from(hash).ok())
+        .ok_or_else(|| Error::InvalidHash(sha256.to_string()))?;
+    let client = config.get_network()?.rpc_client()?;
+    let wasm = get_remote_wasm_from_hash(&client, &xdr::Hash(hash)).await?;
+    if lock::sha256(&wasm) != sha256 {
+        return Err(Error::WasmHashMismatch(sha256.to_string()));
+    }
+    if let Some(cache) = cache {
+        cache.insert(&wasm)?;
+    }
+    Ok(wasm)
+}
@@ -13,7 +13,16 @@ async fn main() {
     });
 
     if let Err(e) = root.run().await {
-        eprintln!("error: {e}");
+        let message = e.to_string();
+        eprintln!("error: {message}");
+        if let Some(error) = e.registry_error() {
+            if !message.contains(error.name()) {
+                eprintln!("\n{error}");
+            }
+            if let Some(hint) = root.hint(error).await {
+                eprintln!("hint: {hint}");
+            }
+        }
         std::process::exit(1);
     }
 }
@@ -67,7 +67,10 @@ impl Cmd {
     }
 
     pub async fn get_contract_id(&self) -> Result<Contract, Error> {
-        let registry = &self.contract.registry(&self.config).await?;
+        let registry = &self
+            .config
+            .registry(self.contract.channel.as_deref())
+            .await?;
         eprintln!("Fetching contract ID via registry...");
         Ok(registry.fetch_contract_id(&self.contract.name).await?)
     }
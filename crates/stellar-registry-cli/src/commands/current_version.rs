@@ -31,13 +31,14 @@ impl Cmd {
     }
 
     pub async fn current_version(&self) -> Result<String, Error> {
-        let registry = self.wasm_name.registry(&self.config).await?;
-        let slop = ["current_version", "--wasm-name", &self.wasm_name.name];
-        let raw = registry
-            .as_contract()
-            .invoke_with_result(&slop, true)
+        let registry = self
+            .config
+            .registry(self.wasm_name.channel.as_deref())
             .await?;
-        Ok(raw.trim_matches('"').to_string())
+        Ok(registry
+            .client()
+            .current_version(&self.wasm_name.name)
+            .await?)
     }
 }
 
@@ -103,7 +103,6 @@ impl Cmd {
 
     pub async fn hash(&self, registry: &Registry) -> Result<xdr::Hash, Error> {
         let res = registry
-            .as_contract()
             .invoke_with_result(&["fetch_hash", "--wasm_name", &self.wasm_name.name], true)
             .await?;
         let res = res.trim_matches('"');
@@ -124,8 +123,14 @@ impl Cmd {
     }
 
     async fn invoke(&self) -> Result<stellar_strkey::Contract, Error> {
-        let target_registry = self.contract_name.registry(&self.config).await?;
-        let wasm_registry = self.wasm_name.registry(&self.config).await?;
+        let target_registry = self
+            .config
+            .registry(self.contract_name.channel.as_deref())
+            .await?;
+        let wasm_registry = self
+            .config
+            .registry(self.wasm_name.channel.as_deref())
+            .await?;
         let cross_registry = target_registry.as_contract().id() != wasm_registry.as_contract().id();
         let client = self.config.rpc_client()?;
         let key = self.config.key_pair()?;
@@ -190,7 +195,9 @@ impl Cmd {
         let account_details = client.get_account(&public_strkey).await?;
         let sequence: i64 = account_details.seq_num.into();
         let tx = util::build_invoke_contract_tx(invoke_contract_args, sequence + 1, 100, &key)?;
-        let assembled = simulate_and_assemble_transaction(&client, &tx, None, None).await?;
+        let assembled = simulate_and_assemble_transaction(&client, &tx, None, None)
+            .await
+            .map_err(stellar_registry_build::Error::from_rpc)?;
         let mut txn = assembled.transaction().clone();
         txn = config
             .sign_soroban_authorizations(&txn, &signers)
@@ -198,7 +205,8 @@ impl Cmd {
             .unwrap_or(txn);
         let return_value = client
             .send_transaction_polling(&config.sign(txn, false).await?)
-            .await?
+            .await
+            .map_err(stellar_registry_build::Error::from_rpc)?
             .return_value()?;
         match return_value {
             ScVal::Address(xdr::ScAddress::Contract(xdr::ContractId(hash))) => {
@@ -94,10 +94,7 @@ impl Cmd {
             slop.push("--version");
             slop.push(version);
         }
-        let res = registry
-            .as_contract()
-            .invoke_with_result(&slop, true)
-            .await?;
+        let res = registry.invoke_with_result(&slop, true).await?;
         let res = res.trim_matches('"');
         Ok(res.parse().unwrap())
     }
@@ -116,7 +113,10 @@ impl Cmd {
     }
 
     async fn invoke(&self) -> Result<stellar_strkey::Contract, Error> {
-        let registry = self.wasm_name.registry(&self.config).await?;
+        let registry = self
+            .config
+            .registry(self.wasm_name.channel.as_deref())
+            .await?;
         let client = self.config.rpc_client()?;
         let key = self.config.key_pair()?;
         let config = &self.config;
@@ -170,7 +170,9 @@ impl Cmd {
         let account_details = client.get_account(&public_strkey).await?;
         let sequence: i64 = account_details.seq_num.into();
         let tx = util::build_invoke_contract_tx(invoke_contract_args, sequence + 1, 100, &key)?;
-        let assembled = simulate_and_assemble_transaction(&client, &tx, None, None).await?;
+        let assembled = simulate_and_assemble_transaction(&client, &tx, None, None)
+            .await
+            .map_err(stellar_registry_build::Error::from_rpc)?;
         let mut txn = assembled.transaction().clone();
         txn = config
             .sign_soroban_authorizations(&txn, &signers)
@@ -178,7 +180,8 @@ impl Cmd {
             .unwrap_or(txn);
         let return_value = client
             .send_transaction_polling(&config.sign(txn, false).await?)
-            .await?
+            .await
+            .map_err(stellar_registry_build::Error::from_rpc)?
             .return_value()?;
         match return_value {
             ScVal::Address(xdr::ScAddress::Contract(xdr::ContractId(hash))) => {
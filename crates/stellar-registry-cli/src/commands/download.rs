@@ -2,7 +2,10 @@ use std::{io::Write, path::PathBuf};
 
 use clap::Parser;
 use stellar_cli::{commands::contract::invoke, xdr};
-use stellar_registry_build::named_registry::PrefixedName;
+use stellar_registry_build::{
+    named_registry::PrefixedName,
+    wasm::{self, Cache},
+};
 
 use crate::commands::global;
 
@@ -61,23 +64,16 @@ impl Cmd {
     }
 
     pub async fn download_bytes(&self) -> Result<Vec<u8>, Error> {
-        let registry = &self.wasm_name.registry(&self.config).await?;
-        let mut slop = vec!["fetch_hash", "--wasm-name", &self.wasm_name.name];
-        let version = self.version.clone().map(|v| format!("\"{v}\""));
-        if let Some(version) = version.as_deref() {
-            slop.push("--version");
-            slop.push(version);
-        }
-        let raw = registry
-            .as_contract()
-            .invoke_with_result(&slop, true)
+        let registry = &self
+            .config
+            .registry(self.wasm_name.channel.as_deref())
+            .await?;
+        let hash = registry
+            .client()
+            .fetch_hash(&self.wasm_name.name, self.version.as_deref())
             .await?;
-        let bytes = stellar_cli::utils::rpc::get_remote_wasm_from_hash(
-            &self.config.get_network()?.rpc_client()?,
-            &raw.trim_matches('"').parse()?,
-        )
-        .await?;
-        Ok(bytes)
+        // Fill the Wasm cache, so builds without the macro's `registry` feature can use it
+        Ok(wasm::fetch(&self.config, &hex::encode(hash), Cache::from_env().as_ref()).await?)
     }
 }
 
@@ -33,7 +33,10 @@ impl Cmd {
     }
 
     pub async fn fetch_contract_id(&self) -> Result<Contract, Error> {
-        let registry = self.contract_name.registry(&self.config).await?;
+        let registry = self
+            .config
+            .registry(self.contract_name.channel.as_deref())
+            .await?;
         Ok(registry.fetch_contract_id(&self.contract_name.name).await?)
     }
 }
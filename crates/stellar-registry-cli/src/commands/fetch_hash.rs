@@ -35,18 +35,15 @@ impl Cmd {
     }
 
     pub async fn fetch_hash(&self) -> Result<String, Error> {
-        let registry = self.wasm_name.registry(&self.config).await?;
-        let mut slop = vec!["fetch_hash", "--wasm-name", &self.wasm_name.name];
-        let version = self.version.clone().map(|v| format!("\"{v}\""));
-        if let Some(version) = version.as_deref() {
-            slop.push("--version");
-            slop.push(version);
-        }
-        let raw = registry
-            .as_contract()
-            .invoke_with_result(&slop, true)
+        let registry = self
+            .config
+            .registry(self.wasm_name.channel.as_deref())
             .await?;
-        Ok(raw.trim_matches('"').to_string())
+        let hash = registry
+            .client()
+            .fetch_hash(&self.wasm_name.name, self.version.as_deref())
+            .await?;
+        Ok(hex::encode(hash))
     }
 }
 
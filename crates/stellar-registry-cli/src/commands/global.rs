@@ -2,11 +2,25 @@ use std::ops::Deref;
 
 use soroban_rpc as rpc;
 use stellar_cli::config::{self};
+use stellar_registry_build::{profile::REGISTRY_ENV, registry::Registry};
+
+/// Options accepted by every command
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct RootArgs {
+    /// Registry to use: a contract ID, a contract alias, or a profile name (see `profile ls`).
+    /// Defaults to the default profile, or else the verified root registry
+    #[arg(long, global = true, env = REGISTRY_ENV)]
+    pub registry: Option<String>,
+}
 
 #[derive(clap::Args, Debug, Clone)]
 pub struct Args {
     #[command(flatten)]
     pub config: config::Args,
+
+    /// Root registry from [`RootArgs::registry`], set by `Root::run`
+    #[arg(skip)]
+    pub registry: Option<String>,
 }
 
 impl Deref for Args {
@@ -21,4 +35,13 @@ impl Args {
     pub fn rpc_client(&self) -> Result<rpc::Client, config::Error> {
         Ok(rpc::Client::new(&self.config.get_network()?.rpc_url)?)
     }
+
+    /// The registry `channel` (a sub-registry name or contract ID), or the root registry
+    /// if `None`, within the registry chosen with `--registry`
+    pub async fn registry(
+        &self,
+        channel: Option<&str>,
+    ) -> Result<Registry, stellar_registry_build::Error> {
+        Registry::new_in(&self.config, self.registry.as_deref(), channel).await
+    }
 }
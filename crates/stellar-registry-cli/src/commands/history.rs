@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use stellar_registry_build::{
+    events::RegistryEvent,
+    history::{self, Index, IndexedEvent},
+    named_registry::PrefixedName,
+};
+
+use crate::commands::global;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Only show events for this contract or Wasm name. A prefix selects the registry,
+    /// e.g. `unverified/<name>`
+    pub name: Option<PrefixedName>,
+
+    /// Only show events published, deployed, or owned by this address
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// Only show events of this type. Can be passed multiple times
+    #[arg(long = "event", value_enum)]
+    pub events: Vec<EventType>,
+
+    #[command(flatten)]
+    pub index: IndexArgs,
+
+    #[command(flatten)]
+    pub config: global::Args,
+}
+
+/// Options for the local event index, shared by commands which replay registry events
+#[derive(clap::Args, Debug, Clone)]
+pub struct IndexArgs {
+    /// Ledger to start from when there is no local index yet.
+    /// Defaults to the oldest ledger retained by the RPC
+    #[arg(long)]
+    pub start_ledger: Option<u32>,
+
+    /// Only read the local index, without fetching new events
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Number of events to request per `getEvents` page
+    #[arg(long, default_value_t = 100)]
+    pub page_size: usize,
+
+    /// Directory for the local event index. Defaults to `registry/history` in the config directory
+    #[arg(long)]
+    pub index_dir: Option<PathBuf>,
+}
+
+impl IndexArgs {
+    /// Load the index for a registry, syncing it with the RPC unless `--offline`
+    pub async fn load(&self, config: &global::Args, contract_id: &str) -> Result<Index, Error> {
+        let path = self.path(config, contract_id)?;
+        let mut index = Index::load(&path)?;
+        if !self.offline {
+            let added = index
+                .sync(
+                    &config.rpc_client()?,
+                    contract_id,
+                    self.start_ledger,
+                    self.page_size,
+                )
+                .await?;
+            index.save(&path)?;
+            eprintln!("Indexed {added} new event(s) for registry {contract_id}");
+        }
+        Ok(index)
+    }
+
+    fn path(&self, config: &global::Args, contract_id: &str) -> Result<PathBuf, Error> {
+        Ok(if let Some(dir) = &self.index_dir {
+            dir.join(format!("{contract_id}.json"))
+        } else {
+            history::default_path(config, contract_id)?
+        })
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventType {
+    Register,
+    Deploy,
+    Publish,
+    UpdateOwner,
+    UpdateAddress,
+    Rename,
+    SecurityFlag,
+    SubRegistry,
+}
+
+impl EventType {
+    fn topic(self) -> &'static str {
+        match self {
+            EventType::Register => "register",
+            EventType::Deploy => "deploy",
+            EventType::Publish => "publish",
+            EventType::UpdateOwner => "update_owner",
+            EventType::UpdateAddress => "update_address",
+            EventType::Rename => "rename",
+            EventType::SecurityFlag => "security_flag",
+            EventType::SubRegistry => "sub_reg",
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] stellar_cli::config::Error),
+    #[error(transparent)]
+    Registry(#[from] stellar_registry_build::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        for IndexedEvent {
+            ledger,
+            ledger_closed_at,
+            event,
+            ..
+        } in self.history().await?
+        {
+            println!(
+                "{ledger:>10} {ledger_closed_at} {:<14} {event}",
+                event.topic()
+            );
+        }
+        Ok(())
+    }
+
+    /// Sync the local index (unless `--offline`) and return the events matching the filters
+    pub async fn history(&self) -> Result<Vec<IndexedEvent>, Error> {
+        let channel = self.name.as_ref().and_then(|n| n.channel.as_deref());
+        let registry = self.config.registry(channel).await?;
+        let contract_id = registry.as_contract().id().to_string();
+        let index = self.index.load(&self.config, &contract_id).await?;
+        Ok(index
+            .events
+            .into_iter()
+            .filter(|e| self.matches(&e.event))
+            .collect())
+    }
+
+    fn matches(&self, event: &RegistryEvent) -> bool {
+        (self.events.is_empty() || self.events.iter().any(|t| t.topic() == event.topic()))
+            && self
+                .name
+                .as_ref()
+                .is_none_or(|name| event.names().contains(&name.name.as_str()))
+            && self
+                .author
+                .as_ref()
+                .is_none_or(|author| event.authors().contains(&author.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters() {
+        let cmd = Cmd::try_parse_from(["history", "b", "--event", "rename"]).unwrap();
+        let event = |old: &str, new: &str| RegistryEvent::Rename {
+            old_name: old.to_string(),
+            new_name: new.to_string(),
+        };
+        assert!(cmd.matches(&event("a", "b")));
+        assert!(!cmd.matches(&event("c", "d")));
+        assert!(!cmd.matches(&RegistryEvent::SecurityFlagContract { flagged: true }));
+
+        let cmd = Cmd::try_parse_from(["history", "--author", "GABC"]).unwrap();
+        assert!(!cmd.matches(&event("a", "b")));
+        assert!(cmd.matches(&RegistryEvent::UpdateOwner {
+            contract_name: "a".to_string(),
+            new_owner: "GABC".to_string(),
+        }));
+    }
+}
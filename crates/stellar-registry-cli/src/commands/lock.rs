@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use stellar_build::lock::{self, Lockfile};
+use stellar_registry_build::wasm;
+
+use crate::commands::global;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Wasm to pin, as `name` or `name@version`, e.g. `unverified/oracle@1.2.0`.
+    /// Defaults to every Wasm and contract already pinned for the current network
+    pub names: Vec<String>,
+
+    /// Pin named contracts imported with `import_contract_id!` instead of Wasm
+    #[arg(long)]
+    pub contract: bool,
+
+    /// Re-resolve pins to the latest version, or the version given with `name@version`
+    #[arg(long)]
+    pub update: bool,
+
+    /// Path to Cargo.toml of the workspace the lockfile belongs to
+    #[arg(long, default_value = "Cargo.toml")]
+    pub manifest_path: PathBuf,
+
+    #[command(flatten)]
+    pub config: global::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Lock(#[from] lock::Error),
+    #[error(transparent)]
+    Config(#[from] stellar_cli::config::Error),
+    #[error(transparent)]
+    Registry(#[from] stellar_registry_build::Error),
+    #[error("No Wasm pinned for this network; pass the names to pin")]
+    NothingToLock,
+    #[error(
+        "Contract `{name}` changed since it was pinned:\n  locked:     {expected}\n  registry:   {actual}\nRun `stellar registry lock --update --contract {name}` if the change is expected"
+    )]
+    ContractChanged {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let path = Lockfile::path(&self.manifest_path)?;
+        let mut lockfile = Lockfile::load(&path)?;
+        let passphrase = self.config.get_network()?.network_passphrase;
+
+        let network = lock::network_name(&passphrase);
+        let (wasm_names, contracts) = if self.names.is_empty() {
+            (
+                lockfile
+                    .entries
+                    .iter()
+                    .filter(|e| e.network == network)
+                    .map(|e| (e.name.clone(), None))
+                    .collect::<Vec<_>>(),
+                lockfile
+                    .contracts
+                    .iter()
+                    .filter(|e| e.network == network)
+                    .map(|e| e.name.clone())
+                    .collect::<Vec<_>>(),
+            )
+        } else if self.contract {
+            (vec![], self.names.clone())
+        } else {
+            (self.names.iter().map(|n| parse_name(n)).collect(), vec![])
+        };
+        if wasm_names.is_empty() && contracts.is_empty() {
+            return Err(Error::NothingToLock);
+        }
+
+        for (name, version) in wasm_names {
+            let pinned = lockfile.get(&name, &passphrase).cloned();
+            match pinned {
+                Some(pinned)
+                    if !self.update && version.as_ref().is_none_or(|v| *v == pinned.version) =>
+                {
+                    let entry = wasm::resolve_in(
+                        &self.config,
+                        self.config.registry.as_deref(),
+                        &name,
+                        Some(&pinned.version),
+                    )
+                    .await?;
+                    if entry.sha256 != pinned.sha256 {
+                        return Err(lock::Error::HashMismatch {
+                            name: pinned.name,
+                            network: pinned.network,
+                            version: pinned.version,
+                            expected: pinned.sha256,
+                            actual: entry.sha256,
+                        }
+                        .into());
+                    }
+                    eprintln!("✅ {name}@{} matches the registry", pinned.version);
+                }
+                _ => {
+                    let entry = wasm::resolve_in(
+                        &self.config,
+                        self.config.registry.as_deref(),
+                        &name,
+                        version.as_deref(),
+                    )
+                    .await?;
+                    eprintln!("🔒 Locked {name}@{} ({})", entry.version, entry.sha256);
+                    lockfile.insert(entry);
+                }
+            }
+        }
+
+        for name in contracts {
+            let entry =
+                wasm::resolve_contract_in(&self.config, self.config.registry.as_deref(), &name)
+                    .await?;
+            match lockfile.get_contract(&name, &passphrase) {
+                Some(pinned) if !self.update => {
+                    if *pinned != entry {
+                        return Err(Error::ContractChanged {
+                            name,
+                            expected: format!("{} ({})", pinned.contract_id, pinned.sha256),
+                            actual: format!("{} ({})", entry.contract_id, entry.sha256),
+                        });
+                    }
+                    eprintln!("✅ {name} matches the registry");
+                }
+                _ => {
+                    eprintln!("🔒 Locked {name} -> {}", entry.contract_id);
+                    lockfile.insert_contract(entry);
+                }
+            }
+        }
+        lockfile.save(&path)?;
+        Ok(())
+    }
+}
+
+/// Split `name@version`, dropping an optional leading `v` from the version
+fn parse_name(s: &str) -> (String, Option<String>) {
+    match s.split_once('@') {
+        Some((name, version)) => (
+            name.to_string(),
+            Some(version.strip_prefix('v').unwrap_or(version).to_string()),
+        ),
+        None => (s.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(parse_name("registry"), ("registry".to_string(), None));
+        assert_eq!(
+            parse_name("unverified/oracle@v1.2.0"),
+            ("unverified/oracle".to_string(), Some("1.2.0".to_string()))
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use clap::{CommandFactory, FromArgMatches, Parser};
+use stellar_registry_build::RegistryError;
 
 pub mod create_alias;
 pub mod current_version;
@@ -10,6 +11,10 @@ pub mod download;
 pub mod fetch_contract_id;
 pub mod fetch_hash;
 pub mod global;
+pub mod history;
+pub mod lock;
+pub mod outdated;
+pub mod profile;
 pub mod publish;
 pub mod publish_hash;
 pub mod register_contract;
@@ -28,8 +33,8 @@ const ABOUT: &str = "Add, manage, and use Wasm packages & named contracts in the
     disable_help_subcommand = true,
 )]
 pub struct Root {
-    // #[clap(flatten)]
-    // pub global_args: global::Args,
+    #[clap(flatten)]
+    pub global_args: global::RootArgs,
     #[command(subcommand)]
     pub cmd: Cmd,
 }
@@ -48,6 +53,9 @@ impl Root {
         Self::from_arg_matches_mut(&mut Self::command().get_matches_from(itr))
     }
     pub async fn run(&mut self) -> Result<(), Error> {
+        if let Some(args) = self.cmd.global_args_mut() {
+            args.registry.clone_from(&self.global_args.registry);
+        }
         match &mut self.cmd {
             Cmd::CurrentVersion(cmd) => cmd.run().await?,
             Cmd::Deploy(deploy) => deploy.run().await?,
@@ -55,6 +63,10 @@ impl Root {
             Cmd::Download(cmd) => cmd.run().await?,
             Cmd::FetchContractId(cmd) => cmd.run().await?,
             Cmd::FetchHash(cmd) => cmd.run().await?,
+            Cmd::History(cmd) => cmd.run().await?,
+            Cmd::Lock(cmd) => cmd.run().await?,
+            Cmd::Outdated(cmd) => cmd.run().await?,
+            Cmd::Profile(cmd) => cmd.run().await?,
             Cmd::Publish(p) => p.run().await?,
             Cmd::PublishHash(cmd) => cmd.run().await?,
             Cmd::CreateAlias(i) => i.run().await?,
@@ -67,6 +79,14 @@ impl Root {
         }
         Ok(())
     }
+
+    /// Suggest a fix for a registry contract error raised while running the command
+    pub async fn hint(&self, error: RegistryError) -> Option<String> {
+        match &self.cmd {
+            Cmd::Publish(p) => p.hint(error).await,
+            _ => error.hint().map(String::from),
+        }
+    }
 }
 
 impl FromStr for Root {
@@ -93,6 +113,14 @@ pub enum Cmd {
     FetchContractId(Box<fetch_contract_id::Cmd>),
     /// Fetch the hash of a published Wasm binary
     FetchHash(Box<fetch_hash::Cmd>),
+    /// Browse the events of a registry, indexing them locally
+    History(Box<history::Cmd>),
+    /// Pin registry Wasm imported with `import_contract_client!` in `stellar-registry.lock`
+    Lock(Box<lock::Cmd>),
+    /// List named contracts running an older published version of their Wasm
+    Outdated(Box<outdated::Cmd>),
+    /// Manage named registry profiles, e.g. for private team registries
+    Profile(Box<profile::Cmd>),
     /// Publish Wasm to registry with package name and semantic version
     Publish(Box<publish::Cmd>),
     /// Publish a Wasm hash (already uploaded) to registry
@@ -111,6 +139,32 @@ pub enum Cmd {
     Version(version::Cmd),
 }
 
+impl Cmd {
+    /// Network and registry options of the command, if it has any
+    fn global_args_mut(&mut self) -> Option<&mut global::Args> {
+        Some(match self {
+            Cmd::CreateAlias(cmd) => &mut cmd.config,
+            Cmd::CurrentVersion(cmd) => &mut cmd.config,
+            Cmd::Deploy(cmd) => &mut cmd.config,
+            Cmd::DeployUnnamed(cmd) => &mut cmd.config,
+            Cmd::Download(cmd) => &mut cmd.config,
+            Cmd::FetchContractId(cmd) => &mut cmd.config,
+            Cmd::FetchHash(cmd) => &mut cmd.config,
+            Cmd::History(cmd) => &mut cmd.config,
+            Cmd::Lock(cmd) => &mut cmd.config,
+            Cmd::Outdated(cmd) => &mut cmd.config,
+            Cmd::Publish(cmd) => &mut cmd.config,
+            Cmd::PublishHash(cmd) => &mut cmd.config,
+            Cmd::RegisterContract(cmd) => &mut cmd.config,
+            Cmd::RenameContract(cmd) => &mut cmd.config,
+            Cmd::UpdateContractAddress(cmd) => &mut cmd.config,
+            Cmd::UpdateContractOwner(cmd) => &mut cmd.config,
+            Cmd::Upgrade(cmd) => &mut cmd.config,
+            Cmd::Profile(_) | Cmd::Version(_) => return None,
+        })
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
@@ -128,6 +182,14 @@ pub enum Error {
     #[error(transparent)]
     FetchHash(#[from] fetch_hash::Error),
     #[error(transparent)]
+    History(#[from] history::Error),
+    #[error(transparent)]
+    Lock(#[from] lock::Error),
+    #[error(transparent)]
+    Outdated(#[from] outdated::Error),
+    #[error(transparent)]
+    Profile(#[from] profile::Error),
+    #[error(transparent)]
     Publish(#[from] publish::Error),
     #[error(transparent)]
     PublishHash(#[from] publish_hash::Error),
@@ -142,3 +204,11 @@ pub enum Error {
     #[error(transparent)]
     Upgrade(#[from] upgrade::Error),
 }
+
+impl Error {
+    /// The error raised by a registry contract this command called, if any
+    pub fn registry_error(&self) -> Option<RegistryError> {
+        std::iter::successors(Some(self as &dyn std::error::Error), |e| e.source())
+            .find_map(|e| e.downcast_ref::<RegistryError>().copied())
+    }
+}
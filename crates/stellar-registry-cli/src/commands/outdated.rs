@@ -0,0 +1,310 @@
+use std::collections::{BTreeMap, HashMap};
+
+use clap::Parser;
+use soroban_rpc as rpc;
+use stellar_cli::xdr::{ContractExecutable, ScContractInstance, ScVal};
+use stellar_registry_build::{
+    events::RegistryEvent, named_registry::PrefixedName, registry::Registry,
+};
+
+use crate::commands::{global, history};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Only include contracts owned by this address
+    #[arg(long)]
+    pub owner: Option<String>,
+
+    #[command(flatten)]
+    pub index: history::IndexArgs,
+
+    #[command(flatten)]
+    pub config: global::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    History(#[from] history::Error),
+    #[error(transparent)]
+    Config(#[from] stellar_cli::config::Error),
+    #[error(transparent)]
+    Rpc(#[from] rpc::Error),
+    #[error(transparent)]
+    Strkey(#[from] stellar_strkey::DecodeError),
+    #[error(transparent)]
+    Registry(#[from] stellar_registry_build::Error),
+}
+
+/// A named contract running an older published version of a Wasm
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outdated {
+    /// Sub-registry the contract is named in, e.g. `unverified`. `None` for the root registry
+    pub channel: Option<String>,
+    pub contract_name: String,
+    pub contract_id: String,
+    pub wasm_name: String,
+    pub current_version: String,
+    pub latest_version: String,
+}
+
+/// Named contracts and published Wasm versions of a registry, replayed from its events
+#[derive(Debug, Default)]
+pub struct RegistryState {
+    /// Contract name to contract ID
+    pub contracts: BTreeMap<String, String>,
+    /// Wasm hash to published Wasm name and version
+    published: HashMap<String, (String, String)>,
+    /// Wasm name to most recently published version
+    latest: HashMap<String, String>,
+    /// Sub-registry name to contract ID
+    pub sub_registries: BTreeMap<String, String>,
+}
+
+impl RegistryState {
+    pub fn from_events<'a>(events: impl IntoIterator<Item = &'a RegistryEvent>) -> Self {
+        let mut state = Self::default();
+        for event in events {
+            match event {
+                RegistryEvent::Register {
+                    contract_name,
+                    contract_id,
+                    ..
+                } => {
+                    state
+                        .contracts
+                        .insert(contract_name.clone(), contract_id.clone());
+                }
+                RegistryEvent::UpdateAddress {
+                    contract_name,
+                    new_address,
+                } => {
+                    state
+                        .contracts
+                        .insert(contract_name.clone(), new_address.clone());
+                }
+                RegistryEvent::Rename { old_name, new_name } => {
+                    if let Some(id) = state.contracts.remove(old_name) {
+                        state.contracts.insert(new_name.clone(), id);
+                    }
+                }
+                RegistryEvent::Publish {
+                    wasm_name,
+                    wasm_hash,
+                    version,
+                    ..
+                } => {
+                    state
+                        .published
+                        .insert(wasm_hash.clone(), (wasm_name.clone(), version.clone()));
+                    // The registry only accepts versions greater than the current one
+                    state.latest.insert(wasm_name.clone(), version.clone());
+                }
+                RegistryEvent::SubRegistry { name, contract_id } => {
+                    state
+                        .sub_registries
+                        .insert(name.clone(), contract_id.clone());
+                }
+                _ => {}
+            }
+        }
+        state
+    }
+
+    /// Compare a contract's on-chain Wasm hash with the latest published version of its Wasm
+    pub fn outdated(&self, contract_name: &str, contract_id: &str, hash: &str) -> Option<Outdated> {
+        let (wasm_name, current_version) = self.published.get(hash)?;
+        let latest_version = self.latest.get(wasm_name)?;
+        (latest_version != current_version).then(|| Outdated {
+            channel: None,
+            contract_name: contract_name.to_string(),
+            contract_id: contract_id.to_string(),
+            wasm_name: wasm_name.clone(),
+            current_version: current_version.clone(),
+            latest_version: latest_version.clone(),
+        })
+    }
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let outdated = self.find_outdated().await?;
+        if outdated.is_empty() {
+            eprintln!("All named contracts are up to date");
+            return Ok(());
+        }
+        print_table(&outdated);
+        Ok(())
+    }
+
+    /// Outdated contracts of the root registry and of each of its sub-registries
+    pub async fn find_outdated(&self) -> Result<Vec<Outdated>, Error> {
+        let root = self.config.registry(None).await?;
+        let (mut outdated, sub_registries) = self.find_outdated_in(&root, None).await?;
+        for (channel, contract_id) in sub_registries {
+            let registry = self.config.registry(Some(&contract_id)).await?;
+            outdated.extend(self.find_outdated_in(&registry, Some(&channel)).await?.0);
+        }
+        Ok(outdated)
+    }
+
+    /// Outdated contracts named in `registry`, and the sub-registries it lists
+    async fn find_outdated_in(
+        &self,
+        registry: &Registry,
+        channel: Option<&str>,
+    ) -> Result<(Vec<Outdated>, BTreeMap<String, String>), Error> {
+        let registry_id = registry.as_contract().id().to_string();
+        let index = self.index.load(&self.config, &registry_id).await?;
+        let state = RegistryState::from_events(index.events.iter().map(|e| &e.event));
+        let client = registry.client();
+        let rpc = self.config.rpc_client()?;
+
+        let mut outdated = vec![];
+        for (name, id) in &state.contracts {
+            if let Some(owner) = &self.owner
+                && client.fetch_contract_owner(name).await?.to_string() != *owner
+            {
+                continue;
+            }
+            if let Some(hash) = wasm_hash(&rpc, id).await?
+                && let Some(o) = state.outdated(name, id, &hash)
+            {
+                outdated.push(Outdated {
+                    channel: channel.map(str::to_string),
+                    ..o
+                });
+            }
+        }
+        Ok((outdated, state.sub_registries))
+    }
+}
+
+impl Outdated {
+    /// Contract name with its sub-registry prefix, e.g. `unverified/<name>`
+    pub fn prefixed_name(&self) -> String {
+        PrefixedName {
+            channel: self.channel.clone(),
+            name: self.contract_name.clone(),
+        }
+        .to_string()
+    }
+}
+
+pub fn print_table(outdated: &[Outdated]) {
+    let width = |f: fn(&Outdated) -> &str, header: &str| {
+        outdated
+            .iter()
+            .map(|o| f(o).len())
+            .max()
+            .unwrap_or_default()
+            .max(header.len())
+    };
+    let names: Vec<String> = outdated.iter().map(Outdated::prefixed_name).collect();
+    let name = names
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or_default()
+        .max("CONTRACT".len());
+    let wasm = width(|o| o.wasm_name.as_str(), "WASM");
+    let current = width(|o| o.current_version.as_str(), "CURRENT");
+    println!(
+        "{:name$}  {:wasm$}  {:current$}  LATEST",
+        "CONTRACT", "WASM", "CURRENT"
+    );
+    for (o, contract_name) in outdated.iter().zip(&names) {
+        println!(
+            "{contract_name:name$}  {:wasm$}  {:current$}  {}",
+            o.wasm_name, o.current_version, o.latest_version
+        );
+    }
+}
+
+/// Hex encoded Wasm hash of a deployed contract, or `None` for built-in contracts such as SACs
+async fn wasm_hash(client: &rpc::Client, contract_id: &str) -> Result<Option<String>, Error> {
+    let contract: stellar_strkey::Contract = contract_id.parse()?;
+    let entry = client.get_contract_data(&contract.0).await?;
+    Ok(match entry.val {
+        ScVal::ContractInstance(ScContractInstance {
+            executable: ContractExecutable::Wasm(hash),
+            ..
+        }) => Some(hex::encode(hash.0)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn publish(wasm_name: &str, version: &str, hash: &str) -> RegistryEvent {
+        RegistryEvent::Publish {
+            wasm_name: wasm_name.to_string(),
+            wasm_hash: hash.to_string(),
+            version: version.to_string(),
+            author: "G".to_string(),
+        }
+    }
+
+    fn register(contract_name: &str, contract_id: &str) -> RegistryEvent {
+        RegistryEvent::Register {
+            contract_name: contract_name.to_string(),
+            contract_id: contract_id.to_string(),
+            sac: false,
+            wasm_hash: None,
+        }
+    }
+
+    #[test]
+    fn finds_outdated_contracts() {
+        let events = [
+            publish("hello", "0.0.1", "aa"),
+            register("first", "C1"),
+            register("second", "C2"),
+            publish("hello", "0.0.2", "bb"),
+            RegistryEvent::Rename {
+                old_name: "second".to_string(),
+                new_name: "renamed".to_string(),
+            },
+        ];
+        let state = RegistryState::from_events(&events);
+        assert_eq!(
+            state.contracts.keys().collect::<Vec<_>>(),
+            vec!["first", "renamed"]
+        );
+        assert_eq!(
+            state.outdated("first", "C1", "aa"),
+            Some(Outdated {
+                channel: None,
+                contract_name: "first".to_string(),
+                contract_id: "C1".to_string(),
+                wasm_name: "hello".to_string(),
+                current_version: "0.0.1".to_string(),
+                latest_version: "0.0.2".to_string(),
+            })
+        );
+        assert_eq!(state.outdated("renamed", "C2", "bb"), None);
+        assert_eq!(state.outdated("other", "C3", "cc"), None);
+    }
+
+    #[test]
+    fn lists_sub_registries() {
+        let events = [RegistryEvent::SubRegistry {
+            name: "unverified".to_string(),
+            contract_id: "CUNVERIFIED".to_string(),
+        }];
+        let state = RegistryState::from_events(&events);
+        assert_eq!(state.sub_registries["unverified"], "CUNVERIFIED");
+
+        let outdated = Outdated {
+            channel: Some("unverified".to_string()),
+            contract_name: "hello".to_string(),
+            contract_id: "C1".to_string(),
+            wasm_name: "hello".to_string(),
+            current_version: "0.0.1".to_string(),
+            latest_version: "0.0.2".to_string(),
+        };
+        assert_eq!(outdated.prefixed_name(), "unverified/hello");
+    }
+}
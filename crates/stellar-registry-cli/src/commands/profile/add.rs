@@ -0,0 +1,52 @@
+use clap::Parser;
+use stellar_registry_build::profile::{self, Profiles};
+
+use crate::commands::global;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of the profile
+    pub name: String,
+
+    /// Registry on the current network: a contract ID or a contract alias
+    pub registry: String,
+
+    /// Also make this the default profile
+    #[arg(long)]
+    pub default: bool,
+
+    #[command(flatten)]
+    pub config: global::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] stellar_cli::config::Error),
+    #[error(transparent)]
+    Registry(#[from] stellar_registry_build::Error),
+    #[error("Profile name \"{0}\" cannot be a contract ID")]
+    NameIsContractId(String),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        if self.name.parse::<stellar_strkey::Contract>().is_ok() {
+            return Err(Error::NameIsContractId(self.name.clone()));
+        }
+        let passphrase = self.config.get_network()?.network_passphrase;
+        let contract_id = profile::resolve_registry(&self.registry, &self.config)?;
+        let mut profiles = Profiles::load(&self.config.locator)?;
+        profiles.insert(&self.name, &passphrase, contract_id);
+        if self.default {
+            profiles.default = Some(self.name.clone());
+        }
+        profiles.save(&self.config.locator)?;
+        eprintln!(
+            "✅ Added registry {contract_id} to profile '{}'{}",
+            self.name,
+            if self.default { " (default)" } else { "" }
+        );
+        Ok(())
+    }
+}
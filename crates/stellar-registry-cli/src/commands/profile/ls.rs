@@ -0,0 +1,37 @@
+use clap::Parser;
+use stellar_cli::config::locator;
+use stellar_registry_build::profile::Profiles;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub locator: locator::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Registry(#[from] stellar_registry_build::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let profiles = Profiles::load(&self.locator)?;
+        if profiles.profiles.is_empty() {
+            eprintln!("No registry profiles. Add one with `stellar registry profile add`");
+            return Ok(());
+        }
+        for (name, networks) in &profiles.profiles {
+            let marker = if profiles.default.as_deref() == Some(name) {
+                "*"
+            } else {
+                " "
+            };
+            println!("{marker} {name}");
+            for (network, id) in networks {
+                println!("    {network}: {id}");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use clap::Parser;
+
+pub mod add;
+pub mod ls;
+pub mod r#use;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    #[command(subcommand)]
+    pub cmd: Command,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub enum Command {
+    /// Add a registry to a profile for the current network
+    Add(add::Cmd),
+    /// List registry profiles
+    Ls(ls::Cmd),
+    /// Set the profile used when no `--registry` is given
+    Use(r#use::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Add(#[from] add::Error),
+    #[error(transparent)]
+    Ls(#[from] ls::Error),
+    #[error(transparent)]
+    Use(#[from] r#use::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        match &self.cmd {
+            Command::Add(cmd) => cmd.run().await?,
+            Command::Ls(cmd) => cmd.run()?,
+            Command::Use(cmd) => cmd.run()?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use clap::Parser;
+use stellar_cli::config::locator;
+use stellar_registry_build::profile::Profiles;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of the profile. Omit to go back to the verified root registry
+    pub name: Option<String>,
+
+    #[command(flatten)]
+    pub locator: locator::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Registry(#[from] stellar_registry_build::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let mut profiles = Profiles::load(&self.locator)?;
+        if let Some(name) = &self.name
+            && !profiles.profiles.contains_key(name)
+        {
+            return Err(stellar_registry_build::Error::UnknownProfile(name.clone()).into());
+        }
+        profiles.default.clone_from(&self.name);
+        profiles.save(&self.locator)?;
+        match &self.name {
+            Some(name) => eprintln!("✅ Using registry profile '{name}' by default"),
+            None => eprintln!("✅ Using the verified root registry by default"),
+        }
+        Ok(())
+    }
+}
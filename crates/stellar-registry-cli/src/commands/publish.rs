@@ -8,7 +8,7 @@ use stellar_cli::{
     config,
     xdr::{ScMetaEntry, ScMetaV0},
 };
-use stellar_registry_build::{named_registry::PrefixedName, registry::Registry};
+use stellar_registry_build::{RegistryError, named_registry::PrefixedName};
 
 use crate::{commands::global, github::Fetcher};
 
@@ -136,13 +136,11 @@ impl Cmd {
             self.config.source_account().await?.to_string()
         };
         args.push(format!("--author={author}"));
-        let registry = Registry::new(
-            &self.config,
-            self.wasm_name.as_ref().and_then(|p| p.channel.as_deref()),
-        )
-        .await?;
+        let registry = self
+            .config
+            .registry(self.wasm_name.as_ref().and_then(|p| p.channel.as_deref()))
+            .await?;
         registry
-            .as_contract()
             .invoke(
                 &args.iter().map(String::as_str).collect::<Vec<_>>(),
                 self.dry_run,
@@ -154,6 +152,74 @@ impl Cmd {
         );
         Ok(())
     }
+
+    /// Suggest a fix for a failed publish, using the current published version where possible
+    pub async fn hint(&self, error: RegistryError) -> Option<String> {
+        if error == RegistryError::VersionMustBeGreaterThanCurrent
+            && let Some(next) = self.next_version().await
+        {
+            return Some(format!("try --binver {next}"));
+        }
+        error.hint().map(String::from)
+    }
+
+    async fn next_version(&self) -> Option<String> {
+        let name = if let Some(PrefixedName { name, .. }) = &self.wasm_name {
+            name.clone()
+        } else {
+            let wasm_bytes = self.get_wasm_bytes().await.ok()?;
+            let spec = contract_spec::Spec::new(&wasm_bytes).ok()?;
+            spec.meta
+                .iter()
+                .find_map(|ScMetaEntry::ScMetaV0(ScMetaV0 { key, val })| {
+                    (key.to_string() == "name").then(|| val.to_string())
+                })?
+        };
+        let registry = self
+            .config
+            .registry(self.wasm_name.as_ref().and_then(|p| p.channel.as_deref()))
+            .await
+            .ok()?;
+        let current = registry.client().current_version(&name).await.ok()?;
+        next_patch_version(&current)
+    }
+}
+
+/// The smallest `major.minor.patch` version greater than `version`: the version itself
+/// without its pre-release, e.g. `1.2.3-rc.1` -> `1.2.3`, or else with its patch bumped
+fn next_patch_version(version: &str) -> Option<String> {
+    let (without_build, _) = version.split_once('+').unwrap_or((version, ""));
+    let (core, pre_release) = without_build
+        .split_once('-')
+        .map_or((without_build, None), |(core, pre)| (core, Some(pre)));
+    let mut parts = core.split('.').map(str::parse::<u64>);
+    let (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    Some(if pre_release.is_some() {
+        format!("{major}.{minor}.{patch}")
+    } else {
+        format!("{major}.{minor}.{}", patch + 1)
+    })
+}
+
+#[cfg(test)]
+mod version_tests {
+    use super::next_patch_version;
+
+    #[test]
+    fn bumps_patch() {
+        assert_eq!(next_patch_version("0.0.2").as_deref(), Some("0.0.3"));
+        assert_eq!(next_patch_version("1.2.3-rc.1").as_deref(), Some("1.2.3"));
+        assert_eq!(
+            next_patch_version("1.2.3+build.5").as_deref(),
+            Some("1.2.4")
+        );
+        assert_eq!(next_patch_version("1.2"), None);
+        assert_eq!(next_patch_version("latest"), None);
+    }
 }
 
 #[cfg(feature = "integration-tests")]
@@ -194,6 +260,10 @@ mod tests {
             .failure()
             .stderr_as_str();
         assert!(stderr.contains("Error(Contract, #8)"));
+        assert!(stderr.contains(
+            "VersionMustBeGreaterThanCurrent: New version must be greater than the most recent version"
+        ));
+        assert!(stderr.contains("try --binver 0.0.3"));
 
         // Different version same wasm
         let stderr = registry
@@ -208,6 +278,7 @@ mod tests {
             .failure()
             .stderr_as_str();
         assert!(stderr.contains("Error(Contract, #11)"));
+        assert!(stderr.contains("HashAlreadyPublished"));
 
         registry
             .registry_cli("publish")
@@ -254,6 +325,10 @@ mod tests {
             .failure()
             .stderr_as_str();
         assert!(stderr.contains("Error(Contract, #8)"));
+        assert!(stderr.contains(
+            "VersionMustBeGreaterThanCurrent: New version must be greater than the most recent version"
+        ));
+        assert!(stderr.contains("try --binver 0.0.3"));
 
         let stderr = registry
             .registry_cli("publish")
@@ -268,6 +343,7 @@ mod tests {
             .stderr_as_str();
 
         assert!(stderr.contains("Error(Contract, #11)"));
+        assert!(stderr.contains("HashAlreadyPublished"));
 
         registry
             .registry_cli("publish")
@@ -1,6 +1,6 @@
 use clap::Parser;
 use stellar_cli::{commands::contract::invoke, config};
-use stellar_registry_build::{named_registry::PrefixedName, registry::Registry};
+use stellar_registry_build::named_registry::PrefixedName;
 
 use crate::commands::global;
 
@@ -60,9 +60,12 @@ impl Cmd {
             &self.version,
         ];
 
-        let registry = Registry::new(&self.config, self.wasm_name.channel.as_deref()).await?;
+        let registry = self
+            .config
+            .registry(self.wasm_name.channel.as_deref())
+            .await?;
 
-        registry.as_contract().invoke(&args, self.dry_run).await?;
+        registry.invoke(&args, self.dry_run).await?;
 
         eprintln!(
             "{}Successfully published hash {} as {}@{}",
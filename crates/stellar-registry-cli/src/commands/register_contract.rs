@@ -1,6 +1,6 @@
 use clap::Parser;
 use stellar_cli::{commands::contract::invoke, config};
-use stellar_registry_build::{named_registry::PrefixedName, registry::Registry};
+use stellar_registry_build::named_registry::PrefixedName;
 
 use crate::commands::global;
 
@@ -55,9 +55,12 @@ impl Cmd {
             &owner,
         ];
 
-        let registry = Registry::new(&self.config, self.contract_name.channel.as_deref()).await?;
+        let registry = self
+            .config
+            .registry(self.contract_name.channel.as_deref())
+            .await?;
 
-        registry.as_contract().invoke(&args, self.dry_run).await?;
+        registry.invoke(&args, self.dry_run).await?;
 
         eprintln!(
             "{}Successfully registered contract '{}' at {}",
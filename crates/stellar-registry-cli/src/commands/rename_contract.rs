@@ -1,6 +1,6 @@
 use clap::Parser;
 use stellar_cli::{commands::contract::invoke, config};
-use stellar_registry_build::{named_registry::PrefixedName, registry::Registry};
+use stellar_registry_build::named_registry::PrefixedName;
 
 use crate::commands::global;
 
@@ -34,7 +34,10 @@ pub enum Error {
 
 impl Cmd {
     pub async fn run(&self) -> Result<(), Error> {
-        let registry = Registry::new(&self.config, self.contract_name.channel.as_deref()).await?;
+        let registry = self
+            .config
+            .registry(self.contract_name.channel.as_deref())
+            .await?;
 
         let args = [
             "rename_contract",
@@ -44,7 +47,7 @@ impl Cmd {
             &self.new_name,
         ];
 
-        registry.as_contract().invoke(&args, self.dry_run).await?;
+        registry.invoke(&args, self.dry_run).await?;
 
         eprintln!(
             "{}Successfully renamed '{}' to '{}'",
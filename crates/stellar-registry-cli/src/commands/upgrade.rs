@@ -1,25 +1,43 @@
+use std::io::{BufRead, Write};
+
 use clap::Parser;
 use stellar_cli::commands::contract::invoke;
 use stellar_registry_build::named_registry::PrefixedName;
 
-use crate::commands::global;
+use crate::commands::{global, history, outdated};
 
 #[derive(Parser, Debug, Clone)]
 pub struct Cmd {
     /// Name of contract to upgrade.  Can use prefix of not using verified registry.
     /// E.g. `unverified/<name>`
-    #[arg(long)]
-    pub contract_name: PrefixedName,
+    #[arg(long, required_unless_present = "all_outdated")]
+    pub contract_name: Option<PrefixedName>,
 
     /// Name of published Wasm.  Can use prefix of not using verified registry.
     /// E.g. `unverified/<name>`
-    #[arg(long)]
-    pub wasm_name: PrefixedName,
+    #[arg(long, required_unless_present = "all_outdated")]
+    pub wasm_name: Option<PrefixedName>,
 
     /// Version of published Wasm, if not specified, the latest version will be fetched
     #[arg(long)]
     pub version: Option<String>,
 
+    /// Upgrade every named contract running an older published version of its Wasm
+    /// to the latest version. See `stellar registry outdated`
+    #[arg(long, conflicts_with_all = ["contract_name", "wasm_name", "version"])]
+    pub all_outdated: bool,
+
+    /// With --all-outdated, only upgrade contracts owned by this address
+    #[arg(long, requires = "all_outdated")]
+    pub owner: Option<String>,
+
+    /// With --all-outdated, skip the confirmation prompt
+    #[arg(long, short = 'y', requires = "all_outdated")]
+    pub yes: bool,
+
+    #[command(flatten)]
+    pub index: history::IndexArgs,
+
     #[command(flatten)]
     pub config: global::Args,
 }
@@ -36,16 +54,29 @@ pub enum Error {
     Config(#[from] stellar_cli::config::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
-    #[error("Upgrade failed: {0:?}")]
-    UpgradeFailed(invoke::Error),
+    #[error("Upgrade failed: {0}")]
+    UpgradeFailed(#[source] stellar_registry_build::Error),
     #[error(transparent)]
     Registry(#[from] stellar_registry_build::Error),
+    #[error(transparent)]
+    Outdated(#[from] outdated::Error),
+    #[error("--contract-name and --wasm-name are required without --all-outdated")]
+    MissingNames,
+    #[error("{failed} of {total} upgrade(s) failed")]
+    UpgradesFailed { failed: usize, total: usize },
 }
 
 impl Cmd {
     pub async fn run(&self) -> Result<(), Error> {
-        let contract_name = &self.contract_name.name;
-        let wasm_name = &self.wasm_name.name;
+        if self.all_outdated {
+            return self.upgrade_all_outdated().await;
+        }
+        let (Some(contract_name), Some(wasm_name)) = (&self.contract_name, &self.wasm_name) else {
+            return Err(Error::MissingNames);
+        };
+        let channel = contract_name.channel.as_deref();
+        let contract_name = &contract_name.name;
+        let wasm_name = &wasm_name.name;
 
         let mut slop = vec![
             "upgrade_contract",
@@ -58,9 +89,8 @@ impl Cmd {
             slop.push("--version");
             slop.push(version);
         }
-        let registry = self.contract_name.registry(&self.config).await?;
+        let registry = self.config.registry(channel).await?;
         registry
-            .as_contract()
             .invoke_with_result(&slop, false)
             .await
             .map_err(Error::UpgradeFailed)?;
@@ -68,13 +98,72 @@ impl Cmd {
             version.to_string()
         } else {
             registry
-                .as_contract()
                 .invoke_with_result(&["current_version", "--wasm-name", wasm_name], true)
                 .await?
         };
         println!("Upgraded {contract_name} to {wasm_name}@{version}",);
         Ok(())
     }
+
+    /// Upgrade each outdated contract in its own registry, after a confirmation prompt
+    async fn upgrade_all_outdated(&self) -> Result<(), Error> {
+        let outdated = outdated::Cmd {
+            owner: self.owner.clone(),
+            index: self.index.clone(),
+            config: self.config.clone(),
+        }
+        .find_outdated()
+        .await?;
+        if outdated.is_empty() {
+            eprintln!("All named contracts are up to date");
+            return Ok(());
+        }
+        outdated::print_table(&outdated);
+        if !self.yes && !confirm(&format!("Upgrade {} contract(s)?", outdated.len()))? {
+            eprintln!("Aborted");
+            return Ok(());
+        }
+
+        let mut failed = 0;
+        for o in &outdated {
+            let client = self.config.registry(o.channel.as_deref()).await?.client();
+            match client
+                .upgrade_contract(
+                    &o.contract_name,
+                    &o.wasm_name,
+                    Some(&o.latest_version),
+                    None,
+                )
+                .await
+            {
+                Ok(_) => println!(
+                    "✅ {}: {}@{} -> {}",
+                    o.prefixed_name(),
+                    o.wasm_name,
+                    o.current_version,
+                    o.latest_version
+                ),
+                Err(e) => {
+                    failed += 1;
+                    println!("❌ {}: {e}", o.prefixed_name());
+                }
+            }
+        }
+        let total = outdated.len();
+        println!("Upgraded {} of {total} contract(s)", total - failed);
+        if failed > 0 {
+            return Err(Error::UpgradesFailed { failed, total });
+        }
+        Ok(())
+    }
+}
+
+fn confirm(prompt: &str) -> Result<bool, std::io::Error> {
+    eprint!("{prompt} [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
 }
 
 #[cfg(feature = "integration-tests")]
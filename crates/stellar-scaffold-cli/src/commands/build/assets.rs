@@ -0,0 +1,126 @@
+//! Classic assets declared under `[<env>.assets]`: their XDR form, the ID of their
+//! Stellar Asset Contract and the ledger key of an account's trustline to them.
+use sha2::{Digest, Sha256};
+use stellar_strkey::{Contract, ed25519};
+use stellar_xdr::curr::{
+    AccountId, AlphaNum4, AlphaNum12, Asset, AssetCode4, AssetCode12, ContractIdPreimage, Hash,
+    HashIdPreimage, HashIdPreimageContractId, LedgerKey, LedgerKeyTrustLine, Limits, PublicKey,
+    TrustLineAsset, Uint256, WriteXdr,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("⛔ ️Invalid asset code {0:?}. Expected up to 12 letters and digits, or native")]
+    InvalidCode(String),
+    #[error("⛔ ️Asset {0:?} needs an issuer")]
+    MissingIssuer(String),
+    #[error(transparent)]
+    Xdr(#[from] stellar_xdr::curr::Error),
+}
+
+/// The asset with `code` issued by `issuer`, or XLM for the code `native`
+pub fn parse(code: &str, issuer: Option<&ed25519::PublicKey>) -> Result<Asset, Error> {
+    if code == "native" {
+        return Ok(Asset::Native);
+    }
+    let Some(issuer) = issuer else {
+        return Err(Error::MissingIssuer(code.to_string()));
+    };
+    if code.is_empty() || code.len() > 12 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(Error::InvalidCode(code.to_string()));
+    }
+    let issuer = account_id(issuer);
+    Ok(if code.len() <= 4 {
+        let mut asset_code = [0; 4];
+        asset_code[..code.len()].copy_from_slice(code.as_bytes());
+        Asset::CreditAlphanum4(AlphaNum4 {
+            asset_code: AssetCode4(asset_code),
+            issuer,
+        })
+    } else {
+        let mut asset_code = [0; 12];
+        asset_code[..code.len()].copy_from_slice(code.as_bytes());
+        Asset::CreditAlphanum12(AlphaNum12 {
+            asset_code: AssetCode12(asset_code),
+            issuer,
+        })
+    })
+}
+
+/// The asset as the CLI expects it: `native` or `CODE:ISSUER`
+pub fn to_cli_arg(asset: &Asset) -> String {
+    let (code, issuer) = match asset {
+        Asset::Native => return "native".to_string(),
+        Asset::CreditAlphanum4(AlphaNum4 { asset_code, issuer }) => {
+            (asset_code.0.as_slice(), issuer)
+        }
+        Asset::CreditAlphanum12(AlphaNum12 { asset_code, issuer }) => {
+            (asset_code.0.as_slice(), issuer)
+        }
+    };
+    let code = String::from_utf8_lossy(code)
+        .trim_end_matches('\0')
+        .to_string();
+    let AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(issuer))) = issuer;
+    format!("{code}:{}", ed25519::PublicKey(*issuer))
+}
+
+/// ID of the asset's Stellar Asset Contract on the network with `network_passphrase`
+pub fn sac_id(asset: &Asset, network_passphrase: &str) -> Result<Contract, Error> {
+    let preimage = HashIdPreimage::ContractId(HashIdPreimageContractId {
+        network_id: Hash(Sha256::digest(network_passphrase.as_bytes()).into()),
+        contract_id_preimage: ContractIdPreimage::Asset(asset.clone()),
+    });
+    let preimage = preimage.to_xdr(Limits::none())?;
+    Ok(Contract(Sha256::digest(preimage).into()))
+}
+
+/// Ledger key of `holder`'s trustline to the asset. XLM needs no trustline.
+pub fn trustline_key(holder: &ed25519::PublicKey, asset: &Asset) -> Option<LedgerKey> {
+    let asset = match asset {
+        Asset::Native => return None,
+        Asset::CreditAlphanum4(a) => TrustLineAsset::CreditAlphanum4(a.clone()),
+        Asset::CreditAlphanum12(a) => TrustLineAsset::CreditAlphanum12(a.clone()),
+    };
+    Some(LedgerKey::Trustline(LedgerKeyTrustLine {
+        account_id: account_id(holder),
+        asset,
+    }))
+}
+
+fn account_id(key: &ed25519::PublicKey) -> AccountId {
+    AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUER: &str = "GBJVY34OWUI7LWLGUGYHEXPZF27SOUKPVOUULS55NGHCHLDSYQLVODR6";
+
+    #[test]
+    fn cli_arg_round_trip() {
+        let issuer = ed25519::PublicKey::from_string(ISSUER).unwrap();
+        let usdc = parse("USDC", Some(&issuer)).unwrap();
+        assert!(matches!(usdc, Asset::CreditAlphanum4(_)));
+        assert_eq!(to_cli_arg(&usdc), format!("USDC:{ISSUER}"));
+        let long = parse("LONGCODE", Some(&issuer)).unwrap();
+        assert_eq!(to_cli_arg(&long), format!("LONGCODE:{ISSUER}"));
+        assert_eq!(to_cli_arg(&parse("native", None).unwrap()), "native");
+
+        assert!(matches!(parse("USDC", None), Err(Error::MissingIssuer(_))));
+        assert!(matches!(
+            parse("TOOLONGASSETCODE", Some(&issuer)),
+            Err(Error::InvalidCode(_))
+        ));
+    }
+
+    #[test]
+    fn native_sac_id() {
+        let id = sac_id(&Asset::Native, "Test SDF Network ; September 2015").unwrap();
+        assert_eq!(
+            id.to_string(),
+            "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC"
+        );
+    }
+}
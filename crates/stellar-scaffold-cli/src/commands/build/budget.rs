@@ -0,0 +1,132 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use cargo_metadata::Package;
+
+use super::{Error, env_toml};
+
+/// Metadata key for the size budget in `[package.metadata.stellar]`, also accepted per
+/// contract in `environments.toml`
+pub const MAX_WASM_SIZE_KB: &str = "max_wasm_size_kb";
+
+/// A contract whose Wasm is larger than its size budget
+#[derive(Debug)]
+pub struct Violation {
+    pub name: String,
+    pub size: u64,
+    pub max_kb: f64,
+    /// Size of the Wasm as of the previous build, if it was recorded
+    pub previous: Option<u64>,
+}
+
+impl fmt::Display for Violation {
+    #[allow(clippy::cast_precision_loss)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size_kb = self.size as f64 / 1024.0;
+        write!(
+            f,
+            "{}: {size_kb:.1} KB exceeds the budget of {} KB by {:.1} KB",
+            self.name,
+            self.max_kb,
+            size_kb - self.max_kb
+        )?;
+        match self.previous {
+            Some(previous) => write!(
+                f,
+                " ({:+.1} KB since the last build)",
+                (self.size as f64 - previous as f64) / 1024.0
+            ),
+            None => write!(f, " (no previous build recorded)"),
+        }
+    }
+}
+
+/// Size budget of a package in KB. A contract's `max_wasm_size_kb` in `environments.toml`
+/// takes precedence over the one in `[package.metadata.stellar]`.
+pub fn max_size_kb(p: &Package, env: Option<&env_toml::Environment>) -> Option<f64> {
+    let name = p.name.replace('-', "_");
+    env.and_then(|env| env.contracts.as_ref())
+        .and_then(|contracts| contracts.get(name.as_str()))
+        .and_then(|contract| contract.max_wasm_size_kb)
+        .or_else(|| {
+            p.metadata
+                .get("stellar")
+                .and_then(|stellar| stellar.get(MAX_WASM_SIZE_KB))
+                .and_then(serde_json::Value::as_f64)
+        })
+}
+
+/// Check the built Wasm of each package against its size budget. If every contract is within
+/// its budget, each size is recorded in `record_dir` so the next build can report the change.
+///
+/// # Errors
+///
+/// Returns [`Error::WasmSizeBudget`] listing every contract over its budget.
+#[allow(clippy::cast_precision_loss)]
+pub fn check(
+    packages: &[Package],
+    env: Option<&env_toml::Environment>,
+    wasm_out_dir: &Path,
+    record_dir: &Path,
+) -> Result<(), Error> {
+    let mut violations = Vec::new();
+    let mut sizes = Vec::new();
+    for p in packages {
+        let Some(max_kb) = max_size_kb(p, env) else {
+            continue;
+        };
+        let name = p.name.replace('-', "_");
+        let wasm = wasm_out_dir.join(&name).with_extension("wasm");
+        let Ok(size) = fs::metadata(&wasm).map(|m| m.len()) else {
+            continue;
+        };
+        let record = record_path(record_dir, &name);
+        let previous = fs::read_to_string(&record)
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        sizes.push((record, size));
+
+        if size as f64 > max_kb * 1024.0 {
+            violations.push(Violation {
+                name: p.name.clone(),
+                size,
+                max_kb,
+                previous,
+            });
+        }
+    }
+    if !violations.is_empty() {
+        return Err(Error::WasmSizeBudget(violations));
+    }
+    fs::create_dir_all(record_dir)
+        .map_err(|e| Error::WasmSizeRecord(record_dir.to_path_buf(), e))?;
+    for (record, size) in sizes {
+        fs::write(&record, size.to_string()).map_err(|e| Error::WasmSizeRecord(record, e))?;
+    }
+    Ok(())
+}
+
+fn record_path(record_dir: &Path, name: &str) -> PathBuf {
+    record_dir.join(name).with_extension("size")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn violation_shows_delta_since_last_build() {
+        let violation = Violation {
+            name: "token".to_string(),
+            size: 70 * 1024,
+            max_kb: 64.0,
+            previous: Some(62 * 1024),
+        };
+        assert_eq!(
+            violation.to_string(),
+            "token: 70.0 KB exceeds the budget of 64 KB by 6.0 KB (+8.0 KB since the last build)"
+        );
+    }
+}
@@ -2,68 +2,162 @@
 use super::env_toml::Network;
 use crate::arg_parsing;
 use crate::arg_parsing::ArgParser;
+use crate::commands::build::assets;
 use crate::commands::build::clients::Error::UpgradeArgsError;
-use crate::commands::build::env_toml::{self, Environment};
+use crate::commands::build::deployments::{self, Deployment, Manifest};
+use crate::commands::build::env_toml::{self, Environment, UpgradeStrategy};
+use crate::commands::build::interpolate::{self, Reference};
+use crate::commands::build::plan::{self, Plan};
 use crate::commands::{PackageManager, PackageManagerSpec};
 use crate::extension::{self, ResolvedExtension};
+use cargo_metadata::semver::{Version, VersionReq};
 use indexmap::IndexMap;
 use regex::Regex;
 use serde_json;
+use sha2::{Digest, Sha256};
 use shlex::split;
+use std::collections::HashMap;
 use std::hash::Hash;
 use std::path::Path;
 use std::process::Command;
 use std::{fmt::Debug, path::PathBuf};
 use stellar_cli::{
-    CommandParser, commands as cli,
+    CommandParser,
+    assembled::simulate_and_assemble_transaction,
+    commands as cli,
     commands::contract::info::shared::{
         self as contract_spec, Args as FetchArgs, Error as FetchError, fetch,
     },
-    config::{UnresolvedMuxedAccount, network, sign_with},
+    commands::txn_result::TxnResult,
+    config::{UnresolvedMuxedAccount, network, secret::Secret, sign_with},
     print::Print,
-    utils::contract_hash,
     utils::contract_spec::Spec,
+    utils::{contract_hash, transaction_hash},
+};
+use stellar_registry_build::{
+    history::{self, Index},
+    named_registry::PrefixedName,
 };
 use stellar_scaffold_ext_types::{
-    CodegenContext, CompileContext, DeployContext, DeployKind, HookName, NetworkConfig,
+    CodegenContext, CompileContext, ContractOutcome, DeployContext, DeployKind, HookName,
+    NetworkConfig,
 };
-use stellar_strkey::{self, Contract};
+use stellar_strkey::{self, Contract, ed25519};
 use stellar_xdr::curr::ScSpecEntry::FunctionV0;
-use stellar_xdr::curr::{Error as xdrError, ScSpecEntry, ScSpecTypeBytesN, ScSpecTypeDef};
+use stellar_xdr::curr::{
+    ContractId, Error as xdrError, Hash, LedgerEntryData, LedgerKey, Limits, ReadXdr, ScAddress,
+    ScSpecEntry, ScSpecTypeBytesN, ScSpecTypeDef, ScVal, SignerKey, Transaction, TrustLineEntry,
+    Uint256,
+};
 
 /// Internal decision about what deploy action to take for a contract.
 /// Resolved before `pre-deploy` fires so the hook always has a clean execution context.
 enum DeployDecision {
     /// No existing alias, or existing contract is not upgradeable — create a new instance.
     Fresh,
-    /// Existing contract upgraded in-place; the returned ID is unchanged.
-    Upgraded(Contract),
+    /// Existing contract can be upgraded in-place; the ID stays the same.
+    Upgrade {
+        id: Contract,
+        current_hash: String,
+        upgrade_fn: UpgradeFn,
+    },
     /// Existing contract already has the target WASM hash — no on-chain action needed.
     Unchanged(Contract),
+    /// Existing contract runs another WASM but is kept as is, per its upgrade strategy.
+    Kept { id: Contract, current_hash: String },
+}
+
+/// Function that upgrades a contract in place
+struct UpgradeFn {
+    name: String,
+    /// Name of its `BytesN<32>` argument, the new WASM hash
+    hash_arg: String,
+    /// Whether it also takes an address, like the legacy `upgrade(new_wasm_hash, operator)`
+    takes_address: bool,
+}
+
+/// A transaction that deployed or upgraded a contract
+struct Sent {
+    /// Hex encoded transaction hash
+    hash: String,
+    /// Ledger the transaction was included in
+    ledger: Option<u32>,
+    return_value: ScVal,
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, clap::ValueEnum)]
-pub enum ScaffoldEnv {
-    Development,
-    Testing,
-    Staging,
-    Production,
+/// A contract ready for its client to be generated
+struct Deployed {
+    id: Contract,
+    /// `None` for contracts with a pinned ID
+    wasm_hash: Option<String>,
+    outcome: ContractOutcome,
+    needs_rebuild: bool,
 }
+
+/// What a build did with one contract, in the order contracts were processed
+#[derive(Debug, Clone)]
+pub struct ContractReport {
+    pub name: String,
+    pub contract_id: Option<String>,
+    pub wasm_hash: Option<String>,
+    pub outcome: ContractOutcome,
+}
+
+/// Name of an environment in `environments.toml`, selected with `STELLAR_SCAFFOLD_ENV`.
+///
+/// Besides the built-in `development`, `testing`, `staging` and `production`, any table
+/// in `environments.toml` can be used, e.g. `preview` or `ci`.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct ScaffoldEnv(String);
+
 impl ScaffoldEnv {
+    pub fn development() -> Self {
+        Self("development".to_string())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Default for `after_deploy` and `allow_http` in `environments.toml`
     pub fn testing_or_development(&self) -> bool {
-        matches!(self, ScaffoldEnv::Testing | ScaffoldEnv::Development)
+        matches!(self.name(), "development" | "testing")
+    }
+
+    /// Default for `deploy` in `environments.toml`: every environment deploys its
+    /// contracts except `staging` and `production`, which require a contract `id`
+    pub fn deploys_by_default(&self) -> bool {
+        !matches!(self.name(), "staging" | "production")
+    }
+}
+
+impl std::str::FromStr for ScaffoldEnv {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.is_empty() {
+            return Err("environment name cannot be empty".to_string());
+        }
+        Ok(Self(name.to_string()))
     }
 }
 
 impl std::fmt::Display for ScaffoldEnv {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", format!("{self:?}").to_lowercase())
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Debug for ScaffoldEnv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
     }
 }
 
 #[derive(clap::Args, Clone, Debug)]
 pub struct Args {
-    #[arg(env = "STELLAR_SCAFFOLD_ENV", value_enum)]
+    #[arg(env = "STELLAR_SCAFFOLD_ENV")]
     pub env: Option<ScaffoldEnv>,
     #[arg(skip)]
     pub workspace_root: Option<std::path::PathBuf>,
@@ -76,6 +170,17 @@ pub struct Args {
     pub extensions: Vec<ResolvedExtension>,
     #[arg(skip)]
     pub compile_ctx: Option<CompileContext>,
+    /// Stop at the first contract that fails to deploy or get a client, instead of
+    /// processing the rest first. The build fails either way
+    #[arg(
+        long,
+        env = "STELLAR_SCAFFOLD_FAIL_FAST",
+        overrides_with = "keep_going"
+    )]
+    pub fail_fast: bool,
+    /// Process every contract even after one fails. Overrides `--fail-fast`
+    #[arg(long, overrides_with = "fail_fast")]
+    pub keep_going: bool,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -104,8 +209,74 @@ pub enum Error {
     BadContractName(String),
     #[error("⛔ ️Invalid contract ID: {0:?}")]
     InvalidContractID(String),
-    #[error("⛔ ️An ID must be set for a contract in production or staging. E.g. <name>.id = C...")]
+    #[error(
+        "⛔ ️{0:?} needs an id, as this environment deploys no contracts (deploy = false). E.g. {0}.id = C..."
+    )]
     MissingContractID(String),
+    #[error(transparent)]
+    Interpolate(#[from] interpolate::Error),
+    #[error(transparent)]
+    Deployments(#[from] deployments::Error),
+    #[error(transparent)]
+    Assets(#[from] assets::Error),
+    #[error("⛔ ️Asset {asset:?} refers to account {account:?}, which isn't in accounts")]
+    UnknownAssetAccount { asset: String, account: String },
+    #[error(
+        "⛔ ️Account {account:?} is funded by {funder:?}, which must be listed before it in accounts"
+    )]
+    UnknownFunder { account: String, funder: String },
+    #[error("⛔ ️Account {account:?} has signer {signer:?}, which isn't in accounts")]
+    UnknownSigner { account: String, signer: String },
+    #[error(
+        "⛔ ️Account {0:?} has a starting_balance but no funded_by. Friendbot funds a fixed amount"
+    )]
+    StartingBalanceWithoutFunder(String),
+    #[error("⛔ ️Account {0:?} can't have both a seed and a secret_env")]
+    SeedAndSecretEnv(String),
+    #[error("⛔ ️Environment variable {var} with the secret key of account {account:?} isn't set")]
+    MissingSecretEnv { account: String, var: String },
+    #[error(
+        "⛔ ️{} contract(s) failed: {}",
+        .0.iter().filter(|r| r.outcome.is_failure()).count(),
+        .0.iter().filter(|r| r.outcome.is_failure()).map(|r| r.name.as_str()).collect::<Vec<_>>().join(", ")
+    )]
+    ContractsFailed(Vec<ContractReport>),
+    #[error("⛔ ️Asset {0:?} has the same name as a contract")]
+    AssetNameConflict(String),
+    #[error(
+        "⛔ ️Asset {0:?} is native XLM, which takes no trustlines or balances. Fund accounts with `funded_by` and `starting_balance` instead"
+    )]
+    NativeAssetHolders(String),
+    #[error(transparent)]
+    StellarCli(#[from] cli::Error),
+    #[error(transparent)]
+    Registry(#[from] stellar_registry_build::Error),
+    #[error("⛔ ️Invalid registry Wasm {0:?}. Expected [channel/]name[@version]")]
+    InvalidRegistryWasm(String),
+    #[error("⛔ ️No published version of {wasm:?} matches {req}; the latest is {latest}")]
+    NoMatchingVersion {
+        wasm: String,
+        req: String,
+        latest: String,
+    },
+    #[error("⛔ ️The plan is for network {plan:?}, but environments.toml uses {network:?}")]
+    PlanNetworkMismatch { plan: String, network: String },
+    #[error(
+        "⛔ ️Not upgrading {name:?} because {reason}. Set `upgrade = \"redeploy\"` to deploy a new contract instead\n{diff}"
+    )]
+    UpgradeFailed {
+        name: String,
+        reason: String,
+        diff: String,
+    },
+    #[error(
+        "⛔ ️Not planning to replace {name:?}, pinned to {id}, with a new contract, which would start without its state. Set `upgrade = \"redeploy\"` to replace it"
+    )]
+    PinnedContractReplaced { name: String, id: String },
+    #[error("⛔ ️{0:?} or its Wasm changed since the plan was made. Make a new plan")]
+    PlanOutdated(String),
+    #[error("⛔ ️Contracts reference each other's IDs in a cycle: {0:?}")]
+    ContractReferenceCycle(Vec<String>),
     #[error("⛔ ️Unable to parse script: {0:?}")]
     ScriptParseFailure(String),
     #[error(transparent)]
@@ -125,6 +296,8 @@ pub enum Error {
     #[error(transparent)]
     ConfigNetwork(#[from] stellar_cli::config::network::Error),
     #[error(transparent)]
+    Config(#[from] stellar_cli::config::Error),
+    #[error(transparent)]
     ContractInvoke(#[from] cli::contract::invoke::Error),
     #[error(transparent)]
     ContractInfo(#[from] cli::contract::info::interface::Error),
@@ -208,10 +381,6 @@ impl Builder {
         }
     }
 
-    fn stellar_scaffold_env(&self) -> ScaffoldEnv {
-        self.scaffold_env
-    }
-
     fn get_config_locator(&self) -> &stellar_cli::config::locator::Args {
         &self.global_args.locator
     }
@@ -286,13 +455,24 @@ impl Builder {
         }
     }
 
+    /// ID of contract `name` from the environment's `deployments/<env>.json` if it lists
+    /// the contract, or else from the local contract aliases
     fn get_contract_alias(
         &self,
         name: &str,
         network: &network::Network,
-    ) -> Result<Option<Contract>, stellar_cli::config::locator::Error> {
-        self.get_config_locator()
-            .get_contract_id(name, &network.network_passphrase)
+    ) -> Result<Option<Contract>, Error> {
+        let deployed = self
+            .manifest(&network.network_passphrase)?
+            .and_then(|mut manifest| manifest.contracts.remove(name));
+        if let Some(deployment) = deployed {
+            return Contract::from_string(&deployment.id)
+                .map(Some)
+                .map_err(|_| Error::InvalidContractID(deployment.id));
+        }
+        Ok(self
+            .get_config_locator()
+            .get_contract_id(name, &network.network_passphrase)?)
     }
 
     async fn get_contract_hash(
@@ -337,13 +517,59 @@ impl Builder {
         config_dir.save_contract_id(passphrase, contract_id, name)
     }
 
+    /// The environment's `deployments/<env>.json`, if it is for `network_passphrase`.
+    /// Networks run locally have no manifest, as they are reset
+    fn manifest(&self, network_passphrase: &str) -> Result<Option<Manifest>, Error> {
+        if self.env.network.run_locally {
+            return Ok(None);
+        }
+        Ok(Manifest::read(
+            &self.workspace_root,
+            &self.scaffold_env,
+            network_passphrase,
+        )?)
+    }
+
+    /// Record a deploy of contract `name` in `deployments/<env>.json`, along with the
+    /// transaction that deployed or upgraded it, unless the network is run locally
+    fn record_deployment(
+        &self,
+        name: &str,
+        contract_id: &Contract,
+        wasm_hash: &str,
+        deploy_kind: DeployKind,
+        sent: Option<&Sent>,
+    ) -> Result<(), Error> {
+        if self.env.network.run_locally {
+            return Ok(());
+        }
+        let deployed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Manifest::record(
+            &self.workspace_root,
+            &self.scaffold_env,
+            &self.network.network_passphrase,
+            name,
+            Deployment {
+                id: contract_id.to_string(),
+                wasm_hash: wasm_hash.to_string(),
+                deploy_kind,
+                tx_hash: sent.map(|sent| sent.hash.clone()),
+                ledger: sent.and_then(|sent| sent.ledger),
+                deployed_at,
+            },
+        )?;
+        Ok(())
+    }
+
     fn create_contract_template(
         &self,
         name: &str,
         contract_id: &str,
         network: &network::Network,
     ) -> Result<(), Error> {
-        let allow_http = if self.stellar_scaffold_env().testing_or_development() {
+        let allow_http = if self.env.allow_http {
             "\n  allowHttp: true,"
         } else {
             ""
@@ -526,50 +752,339 @@ export default new Client.Client({{
         let Some(accounts) = accounts else {
             return Err(Error::NeedAtLeastOneAccount);
         };
+        validate_accounts(accounts)?;
 
         let config = self.get_config_locator();
         let args = &self.global_args;
+        let rpc_client = soroban_rpc::Client::new(&network.rpc_url)?;
         for account in accounts {
-            printer.infoln(format!("Creating keys for {:?}", account.name));
-            // Use provided global args or create default
-
-            let generate_cmd = cli::keys::generate::Cmd {
-                name: account.name.clone().parse()?,
-                fund: true,
-                config_locator: config.clone(),
-                network: to_args(network),
-                seed: None,
-                hd_path: None,
-                as_secret: false,
-                secure_store: false,
-                overwrite: false,
-            };
-
-            match generate_cmd.run(args).await {
-                Err(e) if e.to_string().contains("already exists") => {
-                    printer.blankln(e);
-                    // Check if account exists on chain
-                    let rpc_client = soroban_rpc::Client::new(&network.rpc_url)?;
+            if let Some(var) = &account.secret_env {
+                printer.infoln(format!("Importing keys for {:?} from ${var}", account.name));
+                if !self.import_secret_key(&account.name, var)? {
+                    printer.blankln(format!(
+                        "An identity with the name '{}' already exists",
+                        account.name
+                    ));
+                }
+            } else {
+                printer.infoln(format!("Creating keys for {:?}", account.name));
+                let generate_cmd = cli::keys::generate::Cmd {
+                    name: account.name.clone().parse()?,
+                    fund: false,
+                    config_locator: config.clone(),
+                    network: to_args(network),
+                    seed: account.seed.clone(),
+                    hd_path: None,
+                    as_secret: false,
+                    secure_store: false,
+                    overwrite: false,
+                };
+                match generate_cmd.run(args).await {
+                    Err(e) if e.to_string().contains("already exists") => printer.blankln(e),
+                    other_result => other_result?,
+                }
+            }
 
-                    let public_key_cmd = cli::keys::public_key::Cmd {
+            if !account.fund {
+                continue;
+            }
+            let address = self.account_public_key(&account.name).await?.to_string();
+            if rpc_client.get_account(&address).await.is_ok() {
+                continue;
+            }
+            if let Some(funder) = &account.funded_by {
+                let balance = account.starting_balance.unwrap_or(10_000);
+                printer.infoln(format!(
+                    "Creating {:?} with {balance} XLM from {funder:?}",
+                    account.name
+                ));
+                self.run_stellar(&[
+                    "tx",
+                    "new",
+                    "create-account",
+                    "--destination",
+                    &address,
+                    "--starting-balance",
+                    &(u128::from(balance) * 10_000_000).to_string(),
+                    "--source-account",
+                    funder,
+                ])
+                .await?;
+            } else {
+                printer.infoln("Account not found on chain, funding...");
+                let fund_cmd = cli::keys::fund::Cmd {
+                    network: to_args(network),
+                    address: cli::keys::public_key::Cmd {
                         name: account.name.parse()?,
                         locator: config.clone(),
                         hd_path: None,
-                    };
-                    let address = public_key_cmd.public_key().await?;
-
-                    if (rpc_client.get_account(&address.to_string()).await).is_err() {
-                        printer.infoln("Account not found on chain, funding...");
-                        let fund_cmd = cli::keys::fund::Cmd {
-                            network: to_args(network),
-                            address: public_key_cmd,
-                        };
-                        fund_cmd.run(args).await?;
+                    },
+                };
+                fund_cmd.run(args).await?;
+            }
+        }
+
+        // Signers may be listed after the accounts they sign for
+        for account in accounts {
+            self.handle_signers(account, &rpc_client).await?;
+        }
+        Ok(())
+    }
+
+    /// Save the secret key in environment variable `var` as the keys of account `name`,
+    /// like `stellar keys add`. Keys already saved under `name` are kept, returning `false`
+    fn import_secret_key(&self, name: &str, var: &str) -> Result<bool, Error> {
+        let secret_key = std::env::var(var).map_err(|_| Error::MissingSecretEnv {
+            account: name.to_string(),
+            var: var.to_string(),
+        })?;
+        ed25519::PrivateKey::from_string(&secret_key)?;
+        let locator = self.get_config_locator();
+        if locator.read_identity(name).is_ok() {
+            return Ok(false);
+        }
+        locator.write_identity(&name.parse()?, &Secret::SecretKey { secret_key })?;
+        Ok(true)
+    }
+
+    /// Add the account's `signers` and set its `thresholds` where they differ from the
+    /// account on chain. Thresholds come last, as they may stop the account's own key
+    /// from signing alone.
+    async fn handle_signers(
+        &self,
+        account: &env_toml::Account,
+        rpc_client: &soroban_rpc::Client,
+    ) -> Result<(), Error> {
+        if account.signers.is_empty() && account.thresholds.is_none() {
+            return Ok(());
+        }
+        let printer = self.printer();
+        let name = &account.name;
+        let address = self.account_public_key(name).await?;
+        let entry = rpc_client.get_account(&address.to_string()).await?;
+
+        for (signer, &weight) in &account.signers {
+            let key = self.account_public_key(signer).await?;
+            let current = entry.signers.iter().find_map(|s| match &s.key {
+                SignerKey::Ed25519(Uint256(k)) if *k == key.0 => Some(s.weight),
+                _ => None,
+            });
+            if current == Some(u32::from(weight)) {
+                continue;
+            }
+            printer.infoln(format!(
+                "Adding {signer:?} as a signer of {name:?} with weight {weight}"
+            ));
+            self.run_stellar(&[
+                "tx",
+                "new",
+                "set-options",
+                "--signer",
+                &key.to_string(),
+                "--signer-weight",
+                &weight.to_string(),
+                "--source-account",
+                name,
+            ])
+            .await?;
+        }
+
+        if let Some(thresholds) = account.thresholds {
+            let [_, low, medium, high] = entry.thresholds.0;
+            if [low, medium, high] != [thresholds.low, thresholds.medium, thresholds.high] {
+                printer.infoln(format!("Setting the thresholds of {name:?}"));
+                self.run_stellar(&[
+                    "tx",
+                    "new",
+                    "set-options",
+                    "--low-threshold",
+                    &thresholds.low.to_string(),
+                    "--med-threshold",
+                    &thresholds.medium.to_string(),
+                    "--high-threshold",
+                    &thresholds.high.to_string(),
+                    "--source-account",
+                    name,
+                ])
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Create the environment's assets if they don't exist yet: trustlines, initial
+    /// balances, and Stellar Asset Contracts with their clients
+    async fn handle_assets(&self) -> Result<(), Error> {
+        let Some(assets) = self.env.assets.as_ref() else {
+            return Ok(());
+        };
+        let printer = self.printer();
+        let rpc_client = soroban_rpc::Client::new(&self.network.rpc_url)?;
+        let accounts = self.env.accounts.as_deref().unwrap_or_default();
+        let contracts = self.env.contracts.as_ref();
+
+        for (name, asset) in assets {
+            if contracts.is_some_and(|contracts| contracts.contains_key(name)) {
+                return Err(Error::AssetNameConflict(name.to_string()));
+            }
+            let mut holders: Vec<&String> = Vec::new();
+            for account in asset
+                .trustlines
+                .iter()
+                .chain(asset.balances.keys())
+                .chain(asset.issuer.as_ref())
+            {
+                if !accounts.iter().any(|a| &a.name == account) {
+                    return Err(Error::UnknownAssetAccount {
+                        asset: name.to_string(),
+                        account: account.clone(),
+                    });
+                }
+                if Some(account) != asset.issuer.as_ref() && !holders.contains(&account) {
+                    holders.push(account);
+                }
+            }
+
+            let xdr_asset = self.xdr_asset(asset).await?;
+            if xdr_asset == stellar_xdr::curr::Asset::Native && !holders.is_empty() {
+                return Err(Error::NativeAssetHolders(name.to_string()));
+            }
+            let asset_arg = assets::to_cli_arg(&xdr_asset);
+            for holder in holders {
+                let holder_key = self.account_public_key(holder).await?;
+                let trustline = assets::trustline_key(&holder_key, &xdr_asset)
+                    .expect("only native assets have no trustlines");
+                let balance = match trustline_balance(&rpc_client, trustline).await? {
+                    Some(balance) => balance,
+                    None => {
+                        printer.infoln(format!("Adding a trustline to {name:?} for {holder:?}"));
+                        self.run_stellar(&[
+                            "tx",
+                            "new",
+                            "change-trust",
+                            "--line",
+                            &asset_arg,
+                            "--source-account",
+                            holder,
+                        ])
+                        .await?;
+                        0
                     }
+                };
+                let amount = asset.balances.get(holder.as_str()).copied().unwrap_or(0);
+                if balance == 0 && amount > 0 {
+                    let issuer = asset.issuer.as_deref().unwrap_or_default();
+                    printer.infoln(format!("Paying {amount} {name:?} to {holder:?}"));
+                    self.run_stellar(&[
+                        "tx",
+                        "new",
+                        "payment",
+                        "--destination",
+                        &holder_key.to_string(),
+                        "--asset",
+                        &asset_arg,
+                        "--amount",
+                        &(u128::from(amount) * 10_000_000).to_string(),
+                        "--source-account",
+                        issuer,
+                    ])
+                    .await?;
                 }
-                other_result => other_result?,
             }
+
+            if !asset.sac {
+                continue;
+            }
+            let sac_id = assets::sac_id(&xdr_asset, &self.network.network_passphrase)?;
+            let deployed = match rpc_client.get_contract_data(&sac_id.0).await {
+                Ok(_) => true,
+                Err(soroban_rpc::Error::NotFound(..)) => false,
+                Err(e) => return Err(e.into()),
+            };
+            if !deployed {
+                printer.infoln(format!("Deploying the Stellar Asset Contract of {name:?}"));
+                let source = self.source_account.to_string();
+                let deployer = asset.issuer.as_deref().unwrap_or(&source);
+                self.run_stellar(&[
+                    "contract",
+                    "asset",
+                    "deploy",
+                    "--asset",
+                    &asset_arg,
+                    "--alias",
+                    name,
+                    "--source-account",
+                    deployer,
+                ])
+                .await?;
+            }
+            printer.checkln(format!("Asset {name:?} is ready, with contract {sac_id}"));
+            if asset.client {
+                let rebuild = !deployed
+                    || !self
+                        .workspace_root
+                        .join(format!("packages/{name}"))
+                        .exists();
+                self.generate_contract_bindings(name, &sac_id.to_string(), None, rebuild)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// ID of the Stellar Asset Contract of asset `name`
+    async fn asset_sac_id(&self, name: &str) -> Result<Contract, Error> {
+        let asset = self
+            .env
+            .assets
+            .as_ref()
+            .and_then(|assets| assets.get(name))
+            .ok_or_else(|| interpolate::Error::NotFound(format!("assets.{name}.id")))?;
+        let xdr_asset = self.xdr_asset(asset).await?;
+        Ok(assets::sac_id(
+            &xdr_asset,
+            &self.network.network_passphrase,
+        )?)
+    }
+
+    async fn xdr_asset(&self, asset: &env_toml::Asset) -> Result<stellar_xdr::curr::Asset, Error> {
+        let issuer = match asset.issuer.as_deref() {
+            Some(issuer) => Some(self.account_public_key(issuer).await?),
+            None => None,
+        };
+        Ok(assets::parse(&asset.code, issuer.as_ref())?)
+    }
+
+    async fn account_public_key(&self, name: &str) -> Result<ed25519::PublicKey, Error> {
+        let address = cli::keys::public_key::Cmd {
+            name: name.parse()?,
+            locator: self.get_config_locator().clone(),
+            hd_path: None,
+        }
+        .public_key()
+        .await?;
+        Ok(ed25519::PublicKey::from_string(&address.to_string())?)
+    }
+
+    /// Run a `stellar` command against the environment's network
+    async fn run_stellar(&self, args: &[&str]) -> Result<(), Error> {
+        let config_dir = self.get_config_dir()?;
+        let mut argv = vec!["stellar"];
+        if self.global_args.quiet {
+            argv.push("--quiet");
         }
+        argv.extend_from_slice(args);
+        argv.extend_from_slice(&[
+            "--rpc-url",
+            &self.network.rpc_url,
+            "--network-passphrase",
+            &self.network.network_passphrase,
+            "--config-dir",
+            config_dir
+                .to_str()
+                .expect("we do not support non-utf8 paths"),
+        ]);
+        cli::Root::from_arg_matches(argv)?.run().await?;
         Ok(())
     }
 
@@ -620,25 +1135,43 @@ export default new Client.Client({{
         Ok(())
     }
 
-    async fn handle_contracts(&self, package_names: Vec<String>) -> Result<(), Error> {
+    /// Deploy each contract and generate its client, printing a summary at the end.
+    /// Fails with every contract's report if any of them failed.
+    async fn handle_contracts(
+        &self,
+        package_names: Vec<String>,
+        fail_fast: bool,
+    ) -> Result<Vec<ContractReport>, Error> {
         let printer = self.printer();
         if package_names.is_empty() {
-            return Ok(());
+            return Ok(Vec::new());
         }
         let contracts = self.env.contracts.as_ref();
         let network = &self.network;
-        let env = self.stellar_scaffold_env();
-        if matches!(env, ScaffoldEnv::Production | ScaffoldEnv::Staging) {
+        if !self.env.deploy {
             if let Some(contracts) = contracts {
                 self.handle_production_contracts(contracts).await?;
             }
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         self.validate_contract_names(contracts)?;
 
+        // Contracts deployed from the registry have no local package
+        let registry_contracts = contracts
+            .into_iter()
+            .flatten()
+            .filter(|(name, settings)| {
+                settings.wasm.is_some() && !package_names.iter().any(|p| p == name.as_ref())
+            })
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        let mut package_names = package_names;
+        package_names.extend(registry_contracts);
         let names = Self::maintain_user_ordering(&package_names, contracts);
+        let names = self.order_by_references(names, contracts)?;
 
+        let mut reports = Vec::new();
         for name in names {
             let settings = contracts
                 .and_then(|contracts| contracts.get(name.as_str()))
@@ -650,22 +1183,243 @@ export default new Client.Client({{
                 continue;
             }
 
-            match self
-                .process_single_contract(&name, settings, network, env)
-                .await
-            {
-                Ok(()) => {
-                    printer.checkln(format!("Successfully generated client for: {name}"));
+            let report = self
+                .process_single_contract(&name, &settings, network)
+                .await;
+            match &report.outcome {
+                ContractOutcome::DeployFailed { error } => {
+                    printer.errorln(format!("Failed to deploy {name}: {error}"));
+                }
+                ContractOutcome::CodegenFailed { error } => {
+                    printer.errorln(format!("Failed to generate client for: {name}: {error}"));
                 }
-                Err(e) => {
-                    printer.errorln(format!("Failed to generate client for: {name}: {e}"));
+                _ => printer.checkln(format!("Successfully generated client for: {name}")),
+            }
+            let failed = report.outcome.is_failure();
+            reports.push(report);
+            if failed && fail_fast {
+                printer.warnln("Stopping at the first failure (--fail-fast)");
+                break;
+            }
+        }
+
+        print_summary(&reports, printer);
+        if reports.iter().any(|r| r.outcome.is_failure()) {
+            return Err(Error::ContractsFailed(reports));
+        }
+        Ok(reports)
+    }
+
+    /// Check every `${...}` reference in a contract's scripts before deploying anything,
+    /// and order `names` so that each contract is deployed after the contracts whose IDs
+    /// it references.
+    fn order_by_references(
+        &self,
+        names: Vec<String>,
+        contracts: Option<&IndexMap<Box<str>, env_toml::Contract>>,
+    ) -> Result<Vec<String>, Error> {
+        let accounts = self.env.accounts.as_deref().unwrap_or_default();
+        let pinned = |name: &str| {
+            contracts
+                .and_then(|contracts| contracts.get(name))
+                .is_some_and(|settings| settings.id.is_some())
+        };
+        let has_sac = |name: &str| {
+            self.env
+                .assets
+                .as_ref()
+                .and_then(|assets| assets.get(name))
+                .is_some_and(|asset| asset.sac)
+        };
+        let mut dependencies = HashMap::new();
+        for name in &names {
+            let settings = contracts.and_then(|contracts| contracts.get(name.as_str()));
+            let scripts = settings
+                .into_iter()
+                .flat_map(|s| {
+                    [
+                        s.constructor_args.as_deref(),
+                        s.after_deploy.as_deref(),
+                        s.upgrade_args.as_deref(),
+                        s.migrate.as_deref(),
+                    ]
+                })
+                .flatten();
+            let mut contract_deps = Vec::new();
+            for script in scripts {
+                for reference in interpolate::references(script)? {
+                    let not_found = || interpolate::Error::NotFound(reference.to_string());
+                    match &reference {
+                        Reference::ContractId(dep) if pinned(dep) || dep == name => {}
+                        Reference::ContractId(dep) if names.contains(dep) => {
+                            contract_deps.push(dep.clone());
+                        }
+                        Reference::ContractId(_) => return Err(not_found().into()),
+                        Reference::AccountAddress(account)
+                            if !accounts.iter().any(|a| &a.name == account) =>
+                        {
+                            return Err(not_found().into());
+                        }
+                        Reference::AssetId(asset) if !has_sac(asset) => {
+                            return Err(not_found().into());
+                        }
+                        Reference::Env(var) if std::env::var(var).is_err() => {
+                            return Err(
+                                interpolate::Error::Unresolved(reference.to_string()).into()
+                            );
+                        }
+                        _ => {}
+                    }
                 }
             }
+            dependencies.insert(name.clone(), contract_deps);
+        }
+
+        let mut ordered: Vec<String> = Vec::with_capacity(names.len());
+        let mut remaining = names;
+        while !remaining.is_empty() {
+            let Some(i) = remaining
+                .iter()
+                .position(|name| dependencies[name].iter().all(|dep| ordered.contains(dep)))
+            else {
+                return Err(Error::ContractReferenceCycle(remaining));
+            };
+            ordered.push(remaining.remove(i));
+        }
+        Ok(ordered)
+    }
+
+    /// The value of every `${...}` reference in `script`
+    async fn resolve_references(&self, script: &str) -> Result<HashMap<Reference, String>, Error> {
+        let mut values = HashMap::new();
+        for reference in interpolate::references(script)? {
+            let value = self.resolve_reference(&reference).await?;
+            values.insert(reference, value);
         }
+        Ok(values)
+    }
+
+    /// Split a script line into its source account and arguments, with `${...}` references
+    /// resolved. See [`Self::parse_script_line`].
+    async fn parse_script(&self, line: &str) -> Result<(Option<String>, Vec<String>), Error> {
+        let values = self.resolve_references(line).await?;
+        Self::parse_script_line(line, &values)
+    }
+
+    async fn resolve_reference(&self, reference: &Reference) -> Result<String, Error> {
+        let unresolved = || interpolate::Error::Unresolved(reference.to_string());
+        Ok(match reference {
+            Reference::ContractId(name) => {
+                let pinned = self
+                    .env
+                    .contracts
+                    .as_ref()
+                    .and_then(|contracts| contracts.get(name.as_str()))
+                    .and_then(|settings| settings.id.clone());
+                match pinned {
+                    Some(id) => id,
+                    None => self
+                        .get_contract_alias(name, &self.network)?
+                        .ok_or_else(unresolved)?
+                        .to_string(),
+                }
+            }
+            Reference::AccountAddress(name) => cli::keys::public_key::Cmd {
+                name: name.parse()?,
+                locator: self.get_config_locator().clone(),
+                hd_path: None,
+            }
+            .public_key()
+            .await?
+            .to_string(),
+            Reference::AssetId(name) => self.asset_sac_id(name).await?.to_string(),
+            Reference::NetworkPassphrase => self.network.network_passphrase.clone(),
+            Reference::NetworkRpcUrl => self.network.rpc_url.clone(),
+            Reference::Env(var) => std::env::var(var).map_err(|_| unresolved())?,
+        })
+    }
 
+    /// Download the registry Wasm `wasm`, written as `[channel/]name[@version]`, to the
+    /// path of contract `name`'s local build, so it is deployed like a local contract
+    async fn fetch_registry_wasm(&self, name: &str, wasm: &str) -> Result<(), Error> {
+        let (wasm_name, req) = match wasm.split_once('@') {
+            Some((wasm_name, req)) => (wasm_name, Some(req)),
+            None => (wasm, None),
+        };
+        let config = self.config();
+        let entry = match req {
+            Some(version) if Version::parse(version).is_ok() => {
+                stellar_registry_build::wasm::resolve(&config, wasm_name, Some(version)).await?
+            }
+            Some(req) => {
+                let req = VersionReq::parse(req)
+                    .map_err(|_| Error::InvalidRegistryWasm(wasm.to_string()))?;
+                let version = self.highest_published(&config, wasm_name, &req).await?;
+                stellar_registry_build::wasm::resolve(&config, wasm_name, Some(&version)).await?
+            }
+            None => stellar_registry_build::wasm::resolve(&config, wasm_name, None).await?,
+        };
+        let path = self.get_wasm_path(name);
+        if std::fs::read(&path).is_ok_and(|wasm| stellar_build::lock::sha256(&wasm) == entry.sha256)
+        {
+            self.printer().infoln(format!(
+                "Using {wasm_name}@{} from the registry for {name:?}, already downloaded",
+                entry.version
+            ));
+            return Ok(());
+        }
+        self.printer().infoln(format!(
+            "Using {wasm_name}@{} from the registry for {name:?}",
+            entry.version
+        ));
+        let cache = stellar_registry_build::wasm::Cache::from_env();
+        let bytes =
+            stellar_registry_build::wasm::fetch(&config, &entry.sha256, cache.as_ref()).await?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, bytes)?;
         Ok(())
     }
 
+    /// Highest version of `wasm_name` matching `req`, from the registry's publish events in
+    /// the local event index, which is synced first. The current version is also considered,
+    /// as the RPC may no longer retain the ledger it was published in.
+    async fn highest_published(
+        &self,
+        config: &stellar_cli::config::Args,
+        wasm_name: &str,
+        req: &VersionReq,
+    ) -> Result<String, Error> {
+        let prefixed: PrefixedName = wasm_name.parse().expect("infallible");
+        let registry = prefixed.registry(config).await?;
+        let contract_id = registry.as_contract().id().to_string();
+        let path = history::default_path(config, &contract_id)?;
+        let mut index = Index::load(&path)?;
+        let rpc_client = soroban_rpc::Client::new(&self.network.rpc_url)?;
+        index.sync(&rpc_client, &contract_id, None, 100).await?;
+        index.save(&path)?;
+
+        let current = registry.client().current_version(&prefixed.name).await?;
+        let versions = index
+            .published(&prefixed.name)
+            .into_iter()
+            .map(|(version, _)| version)
+            .chain([current.as_str()])
+            .filter_map(|version| Version::parse(version).ok())
+            .collect::<Vec<_>>();
+        versions
+            .iter()
+            .filter(|version| req.matches(version))
+            .max()
+            .map(ToString::to_string)
+            .ok_or_else(|| Error::NoMatchingVersion {
+                wasm: wasm_name.to_string(),
+                req: req.to_string(),
+                latest: versions.iter().max().map_or(current, ToString::to_string),
+            })
+    }
+
     fn get_wasm_path(&self, contract_name: &str) -> std::path::PathBuf {
         // Check if out_dir was specified and use it, otherwise fall back to target directory
         if let Some(out_dir) = &self.out_dir {
@@ -684,7 +1438,10 @@ export default new Client.Client({{
         let Some(contracts) = contracts else {
             return Ok(());
         };
-        for (name, _) in contracts.iter().filter(|(_, settings)| settings.client) {
+        for (name, _) in contracts
+            .iter()
+            .filter(|(_, settings)| settings.client && settings.wasm.is_none())
+        {
             let wasm_path = self.get_wasm_path(name);
             if !wasm_path.exists() {
                 return Err(Error::BadContractName(name.to_string()));
@@ -696,113 +1453,410 @@ export default new Client.Client({{
     async fn process_single_contract(
         &self,
         name: &str,
-        settings: env_toml::Contract,
+        settings: &env_toml::Contract,
         network: &network::Network,
-        env: ScaffoldEnv,
-    ) -> Result<(), Error> {
+    ) -> ContractReport {
+        let deployed = match self.deploy_single_contract(name, settings, network).await {
+            Ok(deployed) => deployed,
+            Err(e) => {
+                return ContractReport {
+                    name: name.to_string(),
+                    contract_id: None,
+                    wasm_hash: None,
+                    outcome: ContractOutcome::DeployFailed {
+                        error: e.to_string(),
+                    },
+                };
+            }
+        };
+        let contract_id = deployed.id.to_string();
+        let outcome = match self
+            .generate_contract_bindings(
+                name,
+                &contract_id,
+                deployed.wasm_hash.as_deref(),
+                deployed.needs_rebuild,
+            )
+            .await
+        {
+            Ok(()) => deployed.outcome,
+            Err(e) => ContractOutcome::CodegenFailed {
+                error: e.to_string(),
+            },
+        };
+        ContractReport {
+            name: name.to_string(),
+            contract_id: Some(contract_id),
+            wasm_hash: deployed.wasm_hash,
+            outcome,
+        }
+    }
+
+    /// Upload and deploy or upgrade contract `name` as needed, or take its pinned ID
+    async fn deploy_single_contract(
+        &self,
+        name: &str,
+        settings: &env_toml::Contract,
+        network: &network::Network,
+    ) -> Result<Deployed, Error> {
         let printer = self.printer();
-        // Returns (contract_id, wasm_hash, needs_rebuild).
-        // wasm_hash is None for pinned-ID contracts; needs_rebuild is always true for them.
-        let (contract_id, wasm_hash, needs_rebuild) = if let Some(id) = &settings.id {
-            let contract_id =
-                Contract::from_string(id).map_err(|_| Error::InvalidContractID(id.clone()))?;
-            (contract_id, None::<String>, true)
+        if let Some(id) = &settings.id {
+            let id = Contract::from_string(id).map_err(|_| Error::InvalidContractID(id.clone()))?;
+            return Ok(Deployed {
+                id,
+                wasm_hash: None,
+                outcome: ContractOutcome::Pinned,
+                needs_rebuild: true,
+            });
+        }
+        if let Some(wasm) = &settings.wasm {
+            self.fetch_registry_wasm(name, wasm).await?;
+        }
+        let wasm_path = self.get_wasm_path(name);
+        if !wasm_path.exists() {
+            return Err(Error::BadContractName(name.to_string()));
+        }
+        let new_hash = self.upload_contract_wasm(name, &wasm_path).await?;
+        let new_spec = Spec::new(&std::fs::read(&wasm_path)?)?.spec;
+
+        // Determine what deploy action is needed before firing any hooks.
+        let existing_id = self.get_contract_alias(name, network)?;
+        let decision = self
+            .deploy_decision(name, settings, existing_id, &new_hash, &new_spec, network)
+            .await?;
+        if matches!(decision, DeployDecision::Unchanged(_)) {
+            printer.checkln(format!("Contract {name:?} is up to date"));
+        }
+        let (id, deploy_kind, wasm_hash) = self
+            .deploy(name, settings, wasm_path, &new_hash, decision)
+            .await?;
+
+        let needs_rebuild = matches!(deploy_kind, DeployKind::Fresh | DeployKind::Upgraded)
+            || !self
+                .workspace_root
+                .join(format!("packages/{name}"))
+                .exists();
+        Ok(Deployed {
+            id,
+            wasm_hash: Some(wasm_hash),
+            outcome: deploy_kind.into(),
+            needs_rebuild,
+        })
+    }
+
+    /// Decide whether contract `name`, at `existing_id` if it was deployed before, needs
+    /// a fresh deploy, an upgrade or nothing to run the Wasm `new_hash`, following its
+    /// upgrade strategy. Only reads from the network.
+    async fn deploy_decision(
+        &self,
+        name: &str,
+        settings: &env_toml::Contract,
+        existing_id: Option<Contract>,
+        new_hash: &str,
+        new_spec: &[ScSpecEntry],
+        network: &network::Network,
+    ) -> Result<DeployDecision, Error> {
+        let Some(existing_id) = existing_id else {
+            return Ok(DeployDecision::Fresh);
+        };
+        let Some(current_hash) = self.get_contract_hash(&existing_id, network).await? else {
+            return Ok(DeployDecision::Fresh);
+        };
+        if current_hash == new_hash {
+            return Ok(DeployDecision::Unchanged(existing_id));
+        }
+        if settings.upgrade == UpgradeStrategy::Redeploy {
+            return Ok(DeployDecision::Fresh);
+        }
+
+        let existing_spec = fetch_contract_spec(&current_hash, network).await?;
+        let upgrade_fn = settings.upgrade_fn.as_deref().unwrap_or("upgrade");
+        let reason = if settings.upgrade == UpgradeStrategy::Never {
+            "its upgrade strategy is \"never\"".to_string()
         } else {
-            let wasm_path = self.get_wasm_path(name);
-            if !wasm_path.exists() {
-                return Err(Error::BadContractName(name.to_string()));
+            match (
+                Self::find_upgrade_fn(&existing_spec, upgrade_fn),
+                Self::find_upgrade_fn(new_spec, upgrade_fn),
+            ) {
+                (Some(upgrade_fn), Some(_)) => {
+                    return Ok(DeployDecision::Upgrade {
+                        id: existing_id,
+                        current_hash,
+                        upgrade_fn,
+                    });
+                }
+                (None, _) => format!(
+                    "the deployed contract has no `{upgrade_fn}` function taking a BytesN<32> Wasm hash"
+                ),
+                (_, None) => format!(
+                    "the new Wasm has no `{upgrade_fn}` function taking a BytesN<32> Wasm hash, so it couldn't be upgraded again"
+                ),
             }
-            let new_hash = self.upload_contract_wasm(name, &wasm_path).await?;
+        };
+        let diff = format!(
+            "  {existing_id}\n  Wasm {current_hash} -> {new_hash}\n{}",
+            spec_diff(&existing_spec, new_spec)
+        );
 
-            // Determine what deploy action is needed before firing any hooks.
-            let decision = if let Some(existing_id) = self.get_contract_alias(name, network)? {
-                let hash = self.get_contract_hash(&existing_id, network).await?;
-                if let Some(current_hash) = hash {
-                    if current_hash == new_hash {
-                        printer.checkln(format!("Contract {name:?} is up to date"));
-                        DeployDecision::Unchanged(existing_id)
-                    } else {
-                        match self
-                            .try_upgrade_contract(
-                                name,
-                                existing_id,
-                                &current_hash,
-                                &new_hash,
-                                network,
-                            )
-                            .await?
-                        {
-                            Some(upgraded_id) => DeployDecision::Upgraded(upgraded_id),
-                            None => DeployDecision::Fresh,
-                        }
-                    }
-                } else {
-                    DeployDecision::Fresh
-                }
-            } else {
-                DeployDecision::Fresh
-            };
+        match settings.upgrade {
+            UpgradeStrategy::Auto => {
+                self.printer().warnln(format!(
+                    "Redeploying {name:?} instead of upgrading it because {reason}. The new contract starts without the state of the existing one\n{diff}"
+                ));
+                Ok(DeployDecision::Fresh)
+            }
+            UpgradeStrategy::Never => {
+                self.printer().warnln(format!(
+                    "Keeping the existing {name:?} because {reason}\n{diff}"
+                ));
+                Ok(DeployDecision::Kept {
+                    id: existing_id,
+                    current_hash,
+                })
+            }
+            UpgradeStrategy::Fail => Err(Error::UpgradeFailed {
+                name: name.to_string(),
+                reason,
+                diff,
+            }),
+            UpgradeStrategy::Redeploy => Ok(DeployDecision::Fresh),
+        }
+    }
 
-            extension::run_hook(
-                &self.extensions,
-                HookName::PreDeploy,
-                &self.deploy_ctx(name, wasm_path.clone(), &new_hash, None, None),
-                printer,
-            )
-            .await;
-
-            let (contract_id, deploy_kind) = match decision {
-                DeployDecision::Unchanged(id) => (id, DeployKind::Unchanged),
-                DeployDecision::Upgraded(id) => (id, DeployKind::Upgraded),
-                DeployDecision::Fresh => {
-                    let id = self.deploy_contract(name, &new_hash, &settings).await?;
-                    (id, DeployKind::Fresh)
-                }
-            };
+    /// Carry out `decision` for contract `name`, whose Wasm `new_hash` is already
+    /// uploaded, running the deploy hooks and `after_deploy` script
+    async fn deploy(
+        &self,
+        name: &str,
+        settings: &env_toml::Contract,
+        wasm_path: PathBuf,
+        new_hash: &str,
+        decision: DeployDecision,
+    ) -> Result<(Contract, DeployKind, String), Error> {
+        let printer = self.printer();
+        extension::run_hook(
+            &self.extensions,
+            HookName::PreDeploy,
+            &self.deploy_ctx(name, wasm_path.clone(), new_hash, None, None),
+            printer,
+        )
+        .await;
 
-            // Run after_deploy script and save alias only when something changed on-chain.
-            if deploy_kind != DeployKind::Unchanged {
-                if let Some(after_deploy) = settings.after_deploy.as_deref()
-                    && (env == ScaffoldEnv::Development || env == ScaffoldEnv::Testing)
-                {
-                    printer.infoln(format!("Running after_deploy script for {name:?}"));
-                    self.run_after_deploy_script(name, &contract_id, after_deploy)
+        // A kept contract still runs its previous Wasm
+        let mut wasm_hash = new_hash.to_string();
+        let (contract_id, deploy_kind, sent) = match decision {
+            DeployDecision::Unchanged(id) => (id, DeployKind::Unchanged, None),
+            DeployDecision::Kept { id, current_hash } => {
+                wasm_hash = current_hash;
+                (id, DeployKind::Kept, None)
+            }
+            DeployDecision::Upgrade { id, upgrade_fn, .. } => {
+                let sent = self
+                    .upgrade_contract(name, &id, new_hash, &upgrade_fn, settings)
+                    .await?;
+                if let Some(migrate) = settings.migrate.as_deref() {
+                    printer.infoln(format!("Running migrate script for {name:?}"));
+                    self.run_invoke_script(name, &id, migrate, "Migrate")
                         .await?;
                 }
-                self.save_contract_alias(name, &contract_id, network)?;
+                (id, DeployKind::Upgraded, Some(sent))
+            }
+            DeployDecision::Fresh => {
+                let (id, sent) = self.deploy_contract(name, new_hash, settings).await?;
+                (id, DeployKind::Fresh, Some(sent))
             }
-
-            extension::run_hook(
-                &self.extensions,
-                HookName::PostDeploy,
-                &self.deploy_ctx(
-                    name,
-                    wasm_path,
-                    &new_hash,
-                    Some(contract_id.to_string()),
-                    Some(deploy_kind.clone()),
-                ),
-                printer,
-            )
-            .await;
-
-            let needs_rebuild = deploy_kind != DeployKind::Unchanged
-                || !self
-                    .workspace_root
-                    .join(format!("packages/{name}"))
-                    .exists();
-            (contract_id, Some(new_hash), needs_rebuild)
         };
 
-        self.generate_contract_bindings(
-            name,
-            &contract_id.to_string(),
-            wasm_hash.as_deref(),
-            needs_rebuild,
+        // Run after_deploy script and save alias only when something changed on-chain.
+        let changed = matches!(deploy_kind, DeployKind::Fresh | DeployKind::Upgraded);
+        if changed {
+            if self.env.after_deploy
+                && let Some(after_deploy) = settings.after_deploy.as_deref()
+            {
+                printer.infoln(format!("Running after_deploy script for {name:?}"));
+                self.run_invoke_script(name, &contract_id, after_deploy, "After deploy")
+                    .await?;
+            }
+            self.save_contract_alias(name, &contract_id, &self.network)?;
+        }
+        let recorded = self
+            .manifest(&self.network.network_passphrase)?
+            .is_some_and(|manifest| manifest.contracts.contains_key(name));
+        if changed || !recorded {
+            self.record_deployment(
+                name,
+                &contract_id,
+                &wasm_hash,
+                deploy_kind.clone(),
+                sent.as_ref(),
+            )?;
+        }
+
+        extension::run_hook(
+            &self.extensions,
+            HookName::PostDeploy,
+            &self.deploy_ctx(
+                name,
+                wasm_path,
+                &wasm_hash,
+                Some(contract_id.to_string()),
+                Some(deploy_kind.clone()),
+            ),
+            printer,
         )
-        .await?;
+        .await;
 
-        Ok(())
+        Ok((contract_id, deploy_kind, wasm_hash))
+    }
+
+    /// Work out what deploying each contract of the environment would do, without
+    /// changing anything on chain. Contracts are compared against their `id` in
+    /// `environments.toml`, or else their alias.
+    pub async fn plan(&self) -> Result<Plan, Error> {
+        let network = &self.network;
+        let contracts = self.env.contracts.as_ref();
+        let names = contracts
+            .into_iter()
+            .flatten()
+            .filter(|(_, settings)| settings.client)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let names = self.order_by_references(names, contracts)?;
+
+        let mut steps = Vec::with_capacity(names.len());
+        for name in names {
+            let settings = contracts
+                .and_then(|contracts| contracts.get(name.as_str()))
+                .cloned()
+                .unwrap_or_default();
+            if let Some(wasm) = &settings.wasm {
+                self.fetch_registry_wasm(&name, wasm).await?;
+            }
+            let wasm = std::fs::read(self.get_wasm_path(&name))
+                .map_err(|_| Error::BadContractName(name.clone()))?;
+            let mut wasm_hash = hex::encode(Sha256::digest(&wasm));
+            let existing_id = match &settings.id {
+                Some(id) => Some(
+                    Contract::from_string(id).map_err(|_| Error::InvalidContractID(id.clone()))?,
+                ),
+                None => self.get_contract_alias(&name, network)?,
+            };
+            let spec = Spec::new(&wasm)?.spec;
+            let action = match self
+                .deploy_decision(&name, &settings, existing_id, &wasm_hash, &spec, network)
+                .await?
+            {
+                DeployDecision::Fresh => match &settings.id {
+                    Some(id) if settings.upgrade != UpgradeStrategy::Redeploy => {
+                        return Err(Error::PinnedContractReplaced {
+                            name,
+                            id: id.clone(),
+                        });
+                    }
+                    replaces => plan::Action::Fresh {
+                        replaces: replaces.clone(),
+                    },
+                },
+                DeployDecision::Upgrade {
+                    id, current_hash, ..
+                } => plan::Action::Upgrade {
+                    id: id.to_string(),
+                    current_wasm_hash: current_hash,
+                },
+                DeployDecision::Unchanged(id) => plan::Action::Unchanged { id: id.to_string() },
+                DeployDecision::Kept { id, current_hash } => {
+                    wasm_hash = current_hash;
+                    plan::Action::Kept { id: id.to_string() }
+                }
+            };
+            steps.push(plan::Step {
+                name,
+                wasm_hash,
+                action,
+            });
+        }
+        Ok(Plan {
+            env: self.scaffold_env.to_string(),
+            network_passphrase: network.network_passphrase.clone(),
+            contracts: steps,
+        })
+    }
+
+    /// Execute exactly `plan`, failing before touching a contract if it or its Wasm
+    /// changed since the plan was made. Returns the ID of every contract in the plan.
+    pub async fn apply(&self, plan: &Plan) -> Result<Vec<(String, Contract)>, Error> {
+        let network = &self.network;
+        if plan.network_passphrase != network.network_passphrase {
+            return Err(Error::PlanNetworkMismatch {
+                plan: plan.network_passphrase.clone(),
+                network: network.network_passphrase.clone(),
+            });
+        }
+        let parse_id =
+            |id: &str| Contract::from_string(id).map_err(|_| Error::InvalidContractID(id.into()));
+
+        let mut ids = Vec::with_capacity(plan.contracts.len());
+        for step in &plan.contracts {
+            let name = step.name.as_str();
+            let settings = self
+                .env
+                .contracts
+                .as_ref()
+                .and_then(|contracts| contracts.get(name))
+                .cloned()
+                .unwrap_or_default();
+            let decision = match &step.action {
+                plan::Action::Unchanged { id } | plan::Action::Kept { id } => {
+                    ids.push((step.name.clone(), parse_id(id)?));
+                    continue;
+                }
+                plan::Action::Upgrade {
+                    id,
+                    current_wasm_hash,
+                } => {
+                    let id = parse_id(id)?;
+                    let current_hash = self.get_contract_hash(&id, network).await?;
+                    if current_hash.as_ref() != Some(current_wasm_hash) {
+                        return Err(Error::PlanOutdated(step.name.clone()));
+                    }
+                    let existing_spec = fetch_contract_spec(current_wasm_hash, network).await?;
+                    let upgrade_fn = Self::find_upgrade_fn(
+                        &existing_spec,
+                        settings.upgrade_fn.as_deref().unwrap_or("upgrade"),
+                    )
+                    .ok_or_else(|| Error::PlanOutdated(step.name.clone()))?;
+                    DeployDecision::Upgrade {
+                        id,
+                        current_hash: current_wasm_hash.clone(),
+                        upgrade_fn,
+                    }
+                }
+                plan::Action::Fresh { replaces } => {
+                    if settings.id != *replaces {
+                        return Err(Error::PlanOutdated(step.name.clone()));
+                    }
+                    DeployDecision::Fresh
+                }
+            };
+
+            if let Some(wasm) = &settings.wasm {
+                self.fetch_registry_wasm(name, wasm).await?;
+            }
+            let wasm_path = self.get_wasm_path(name);
+            let wasm =
+                std::fs::read(&wasm_path).map_err(|_| Error::BadContractName(step.name.clone()))?;
+            if hex::encode(Sha256::digest(&wasm)) != step.wasm_hash {
+                return Err(Error::PlanOutdated(step.name.clone()));
+            }
+
+            let new_hash = self.upload_contract_wasm(name, &wasm_path).await?;
+            let (contract_id, ..) = self
+                .deploy(name, &settings, wasm_path, &new_hash, decision)
+                .await?;
+            self.printer().checkln(format!("Applied {step}"));
+            ids.push((step.name.clone(), contract_id));
+        }
+        Ok(ids)
     }
 
     async fn upload_contract_wasm(
@@ -832,7 +1886,13 @@ export default new Client.Client({{
         Ok(hash)
     }
 
-    fn parse_script_line(line: &str) -> Result<(Option<String>, Vec<String>), Error> {
+    /// Run each `$(...)` in `line`, split the result into arguments and then replace the
+    /// `${...}` references in each argument with `values`, so values with spaces or quotes
+    /// stay one argument. Inside `$(...)` the values are shell-quoted instead.
+    fn parse_script_line(
+        line: &str,
+        values: &HashMap<Reference, String>,
+    ) -> Result<(Option<String>, Vec<String>), Error> {
         let re = Regex::new(r"\$\((.*?)\)").expect("Invalid regex pattern");
         let (shell, flag) = if cfg!(windows) {
             ("cmd", "/C")
@@ -840,9 +1900,20 @@ export default new Client.Client({{
             ("sh", "-c")
         };
 
-        let resolved_line = Self::resolve_line(&re, line, shell, flag)?;
+        let quoted = values
+            .iter()
+            .map(|(reference, value)| {
+                let quoted = shlex::try_quote(value)
+                    .map_err(|_| Error::ScriptParseFailure(value.clone()))?;
+                Ok((reference.clone(), quoted.into_owned()))
+            })
+            .collect::<Result<HashMap<_, _>, Error>>()?;
+        let resolved_line = Self::resolve_line(&re, line, shell, flag, &quoted)?;
         let parts = split(&resolved_line)
-            .ok_or_else(|| Error::ScriptParseFailure(resolved_line.clone()))?;
+            .ok_or_else(|| Error::ScriptParseFailure(resolved_line.clone()))?
+            .iter()
+            .map(|part| interpolate::substitute(part, values))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let (source_account, command_parts): (Vec<_>, Vec<_>) = parts
             .into_iter()
@@ -863,9 +1934,10 @@ export default new Client.Client({{
         name: &str,
         hash: &str,
         settings: &env_toml::Contract,
-    ) -> Result<Contract, Error> {
-        let source = self.source_account.to_string();
+    ) -> Result<(Contract, Sent), Error> {
+        let mut source = self.source_account.to_string();
         let mut deploy_args = vec![
+            "--build-only".to_string(),
             format!("--alias={name}"),
             format!("--wasm-hash={hash}"),
             "--config-dir".to_string(),
@@ -875,18 +1947,16 @@ export default new Client.Client({{
                 .to_string(),
         ];
         if let Some(constructor_script) = &settings.constructor_args {
-            let (source_account, mut args) = Self::parse_script_line(constructor_script)?;
+            let (source_account, mut args) = self.parse_script(constructor_script).await?;
 
             if let Some(account) = source_account {
-                deploy_args.extend_from_slice(&["--source-account".to_string(), account]);
-            } else {
-                deploy_args.extend_from_slice(&["--source".to_string(), source]);
+                source = account;
             }
-
+            deploy_args.extend_from_slice(&["--source-account".to_string(), source.clone()]);
             deploy_args.push("--".to_string());
             deploy_args.append(&mut args);
         } else {
-            deploy_args.extend_from_slice(&["--source".to_string(), source]);
+            deploy_args.extend_from_slice(&["--source".to_string(), source.clone()]);
         }
 
         let deploy_arg_refs: Vec<&str> = deploy_args
@@ -894,93 +1964,131 @@ export default new Client.Client({{
             .map(std::string::String::as_str)
             .collect();
         let deploy_cmd = cli::contract::deploy::wasm::Cmd::parse_arg_vec(&deploy_arg_refs)?;
-        let contract_id = deploy_cmd
+        let TxnResult::Txn(tx) = deploy_cmd
             .execute(
                 &self.config(),
                 self.global_args.quiet,
                 self.global_args.no_cache,
             )
             .await?
-            .into_result()
-            .expect("no contract id returned by 'contract deploy'");
+        else {
+            panic!("no transaction returned by 'contract deploy --build-only'");
+        };
+        let sent = self.send(*tx, &source).await?;
+        let ScVal::Address(ScAddress::Contract(ContractId(Hash(id)))) = &sent.return_value else {
+            return Err(Error::InvalidContractID(format!("{:?}", sent.return_value)));
+        };
+
+        Ok((Contract(*id), sent))
+    }
 
-        Ok(contract_id)
+    /// Simulate `tx`, built by a stellar-cli command with `--build-only`, then sign it as
+    /// `source` and send it
+    async fn send(&self, tx: Transaction, source: &str) -> Result<Sent, Error> {
+        let mut config = self.config();
+        config.source_account = source.parse()?;
+        let client = soroban_rpc::Client::new(&self.network.rpc_url)?;
+        let assembled = simulate_and_assemble_transaction(&client, &tx, None, None).await?;
+        let tx = assembled.transaction().clone();
+        let tx = config
+            .sign_soroban_authorizations(&tx, &[])
+            .await?
+            .unwrap_or(tx);
+        let hash = hex::encode(transaction_hash(&tx, &self.network.network_passphrase)?);
+        let res = client
+            .send_transaction_polling(&config.sign(tx, self.global_args.quiet).await?)
+            .await?;
+        Ok(Sent {
+            hash,
+            ledger: res.ledger,
+            return_value: res.return_value()?,
+        })
     }
 
-    async fn try_upgrade_contract(
+    async fn upgrade_contract(
         &self,
         name: &str,
-        existing_contract_id: Contract,
-        existing_hash: &str,
+        existing_contract_id: &Contract,
         hash: &str,
-        network: &network::Network,
-    ) -> Result<Option<Contract>, Error> {
-        let printer = self.printer();
-        let existing_spec = fetch_contract_spec(existing_hash, network).await?;
-        let spec_to_upgrade = fetch_contract_spec(hash, network).await?;
-        let Some(legacy_upgradeable) = Self::is_legacy_upgradeable(&existing_spec) else {
-            return Ok(None);
-        };
-
-        if Self::is_legacy_upgradeable(&spec_to_upgrade).is_none() {
-            printer.warnln("New WASM is not upgradable. Contract will be redeployed instead of being upgraded.");
-            return Ok(None);
+        upgrade_fn: &UpgradeFn,
+        settings: &env_toml::Contract,
+    ) -> Result<Sent, Error> {
+        let mut source = self.source_account.to_string();
+        let mut fn_args = Vec::new();
+        if let Some(upgrade_args) = &settings.upgrade_args {
+            let (source_account, mut args) = self.parse_script(upgrade_args).await?;
+            if let Some(account) = source_account {
+                source = account;
+            }
+            fn_args.append(&mut args);
+        } else if upgrade_fn.takes_address {
+            let upgrade_operator = ArgParser::get_upgrade_args(name).map_err(UpgradeArgsError)?;
+            fn_args.extend(["--operator".to_string(), upgrade_operator]);
         }
 
         let existing_contract_id_str = existing_contract_id.to_string();
-        let source = self.source_account.to_string();
-        let mut redeploy_args = vec![
+        let hash_arg = format!("--{}", upgrade_fn.hash_arg);
+        let mut upgrade_args = vec![
+            "--build-only",
             "--source",
             source.as_str(),
             "--id",
             existing_contract_id_str.as_str(),
             "--",
-            "upgrade",
-            "--new_wasm_hash",
+            upgrade_fn.name.as_str(),
+            hash_arg.as_str(),
             hash,
         ];
-        let invoke_cmd = if legacy_upgradeable {
-            let upgrade_operator = ArgParser::get_upgrade_args(name).map_err(UpgradeArgsError)?;
-            redeploy_args.push("--operator");
-            redeploy_args.push(&upgrade_operator);
-            cli::contract::invoke::Cmd::parse_arg_vec(&redeploy_args)
-        } else {
-            cli::contract::invoke::Cmd::parse_arg_vec(&redeploy_args)
-        }?;
-        invoke_cmd
+        upgrade_args.extend(fn_args.iter().map(String::as_str));
+        let invoke_cmd = cli::contract::invoke::Cmd::parse_arg_vec(&upgrade_args)?;
+        let TxnResult::Txn(tx) = invoke_cmd
             .execute(
                 &self.config(),
                 self.global_args.quiet,
                 self.global_args.no_cache,
             )
             .await?
-            .into_result()
-            .expect("no result returned by 'contract invoke'");
-
-        Ok(Some(existing_contract_id))
+        else {
+            panic!("no transaction returned by 'contract invoke --build-only'");
+        };
+        self.send(*tx, &source).await
     }
 
-    /// Returns: none if not upgradable, Some(true) if legacy upgradeable, Some(false) if new upgradeable
-    fn is_legacy_upgradeable(spec: &[ScSpecEntry]) -> Option<bool> {
-        spec.iter()
+    /// Function `name` of a contract's spec if it can upgrade the contract, i.e. takes a
+    /// `BytesN<32>` WASM hash
+    fn find_upgrade_fn(spec: &[ScSpecEntry], name: &str) -> Option<UpgradeFn> {
+        let function = spec
+            .iter()
             .filter_map(|x| if let FunctionV0(e) = x { Some(e) } else { None })
-            .filter(|x| x.name.to_string() == "upgrade")
-            .find(|x| {
-                x.inputs
-                    .iter()
-                    .any(|y| matches!(y.type_, ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 32 })))
-            })
-            .map(|x| x.inputs.iter().any(|y| y.type_ == ScSpecTypeDef::Address))
+            .find(|x| x.name.to_string() == name)?;
+        let hash_arg = function
+            .inputs
+            .iter()
+            .find(|y| matches!(y.type_, ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 32 })))?;
+        Some(UpgradeFn {
+            name: name.to_string(),
+            hash_arg: hash_arg.name.to_string(),
+            takes_address: function
+                .inputs
+                .iter()
+                .any(|y| y.type_ == ScSpecTypeDef::Address),
+        })
     }
 
-    fn resolve_line(re: &Regex, line: &str, shell: &str, flag: &str) -> Result<String, Error> {
+    fn resolve_line(
+        re: &Regex,
+        line: &str,
+        shell: &str,
+        flag: &str,
+        quoted: &HashMap<Reference, String>,
+    ) -> Result<String, Error> {
         let mut result = String::new();
         let mut last_match = 0;
         for cap in re.captures_iter(line) {
             let whole_match = cap.get(0).unwrap();
             result.push_str(&line[last_match..whole_match.start()]);
-            let cmd = &cap[1];
-            let output = Self::execute_subcommand(shell, flag, cmd)?;
+            let cmd = interpolate::substitute(&cap[1], quoted)?;
+            let output = Self::execute_subcommand(shell, flag, &cmd)?;
             result.push_str(&output);
             last_match = whole_match.end();
         }
@@ -1007,23 +2115,26 @@ export default new Client.Client({{
         }
     }
 
-    async fn run_after_deploy_script(
+    /// Run each line of `script` as a `stellar contract invoke` of contract `name`,
+    /// like `after_deploy` and `migrate`
+    async fn run_invoke_script(
         &self,
         name: &str,
         contract_id: &Contract,
-        after_deploy_script: &str,
+        script: &str,
+        label: &str,
     ) -> Result<(), Error> {
         let printer = self.printer();
         let config_dir_path = self.get_config_dir()?;
         let config_dir = config_dir_path.to_str().unwrap();
         let source = self.source_account.to_string();
-        for line in after_deploy_script.lines() {
+        for line in script.lines() {
             let line = line.trim();
             if line.is_empty() {
                 continue;
             }
 
-            let (source_account, command_parts) = Self::parse_script_line(line)?;
+            let (source_account, command_parts) = self.parse_script(line).await?;
 
             let contract_id_arg = contract_id.to_string();
             let mut args = vec!["--id", &contract_id_arg, "--config-dir", config_dir];
@@ -1050,7 +2161,7 @@ export default new Client.Client({{
             printer.infoln(format!("  ↳ Result: {result:?}"));
         }
         printer.checkln(format!(
-            "After deploy script for {name:?} completed successfully"
+            "{label} script for {name:?} completed successfully"
         ));
         Ok(())
     }
@@ -1066,7 +2177,7 @@ impl Args {
             .workspace_root
             .as_ref()
             .expect("workspace_root must be set before running");
-        let env = self.env.unwrap_or(ScaffoldEnv::Development);
+        let env = self.env.clone().unwrap_or_else(ScaffoldEnv::development);
         let global_args = self.global_args.clone().unwrap_or_default();
 
         let Some(current_env) = env_toml::Environment::get(workspace_root, &env)? else {
@@ -1110,19 +2221,23 @@ impl Args {
         Ok(builder)
     }
 
-    pub async fn run(&self, package_names: Vec<String>) -> Result<(), Error> {
+    pub async fn run(&self, package_names: Vec<String>) -> Result<Vec<ContractReport>, Error> {
         let builder = match self.builder() {
             Ok(builder) => builder,
             Err(Error::MissingWorkspace) => {
-                return Ok(());
+                return Ok(Vec::new());
             }
             Err(e) => {
                 return Err(e);
             }
         };
         builder.handle_accounts().await?;
-        builder.handle_contracts(package_names).await?;
-        Ok(())
+        if builder.env.deploy {
+            builder.handle_assets().await?;
+        }
+        builder
+            .handle_contracts(package_names, self.fail_fast && !self.keep_going)
+            .await
     }
 }
 
@@ -1160,6 +2275,118 @@ fn to_args(
     }
 }
 
+/// Print what happened to each contract, failures last
+fn print_summary(reports: &[ContractReport], printer: &Print) {
+    if reports.is_empty() {
+        return;
+    }
+    let width = reports
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or_default();
+    printer.infoln("Summary:");
+    for report in reports.iter().filter(|r| !r.outcome.is_failure()) {
+        let status = match report.outcome {
+            ContractOutcome::Deployed => "deployed",
+            ContractOutcome::Upgraded => "upgraded",
+            ContractOutcome::Pinned => "pinned",
+            ContractOutcome::Kept => "kept",
+            _ => "unchanged",
+        };
+        let id = report.contract_id.as_deref().unwrap_or_default();
+        printer.blankln(format!("  {:<width$}  {status:<9}  {id}", report.name));
+    }
+    for report in reports.iter().filter(|r| r.outcome.is_failure()) {
+        let status = match report.outcome {
+            ContractOutcome::CodegenFailed { .. } => "codegen failed",
+            _ => "deploy failed",
+        };
+        printer.errorln(format!("{:<width$}  {status}", report.name));
+    }
+}
+
+/// Check that accounts are funded by accounts listed before them, are signed for by
+/// known accounts, and don't combine settings that contradict each other
+fn validate_accounts(accounts: &[env_toml::Account]) -> Result<(), Error> {
+    for (i, account) in accounts.iter().enumerate() {
+        let name = &account.name;
+        if account.seed.is_some() && account.secret_env.is_some() {
+            return Err(Error::SeedAndSecretEnv(name.clone()));
+        }
+        if account.starting_balance.is_some() && account.funded_by.is_none() {
+            return Err(Error::StartingBalanceWithoutFunder(name.clone()));
+        }
+        if let Some(funder) = &account.funded_by
+            && !accounts[..i].iter().any(|a| &a.name == funder)
+        {
+            return Err(Error::UnknownFunder {
+                account: name.clone(),
+                funder: funder.clone(),
+            });
+        }
+        if let Some(signer) = account
+            .signers
+            .keys()
+            .find(|signer| !accounts.iter().any(|a| &a.name == *signer))
+        {
+            return Err(Error::UnknownSigner {
+                account: name.clone(),
+                signer: signer.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Balance in stroops of a trustline, or `None` if it doesn't exist
+async fn trustline_balance(
+    rpc_client: &soroban_rpc::Client,
+    trustline: LedgerKey,
+) -> Result<Option<i64>, Error> {
+    let response = rpc_client.get_ledger_entries(&[trustline]).await?;
+    let Some(entry) = response.entries.into_iter().flatten().next() else {
+        return Ok(None);
+    };
+    Ok(
+        match LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none())? {
+            LedgerEntryData::Trustline(TrustLineEntry { balance, .. }) => Some(balance),
+            _ => None,
+        },
+    )
+}
+
+/// Functions added, removed and changed between two contract specs, one per line
+fn spec_diff(existing: &[ScSpecEntry], new: &[ScSpecEntry]) -> String {
+    let functions = |spec: &[ScSpecEntry]| {
+        spec.iter()
+            .filter_map(|entry| match entry {
+                FunctionV0(f) => Some((f.name.to_string(), f.clone())),
+                _ => None,
+            })
+            .collect::<std::collections::BTreeMap<_, _>>()
+    };
+    let existing = functions(existing);
+    let new = functions(new);
+    let mut lines = Vec::new();
+    for (name, f) in &existing {
+        match new.get(name) {
+            None => lines.push(format!("  - fn {name}")),
+            Some(new_f) if new_f != f => lines.push(format!("  ~ fn {name}")),
+            Some(_) => {}
+        }
+    }
+    lines.extend(
+        new.keys()
+            .filter(|name| !existing.contains_key(*name))
+            .map(|name| format!("  + fn {name}")),
+    );
+    if lines.is_empty() {
+        lines.push("  (no change to the contract's functions)".to_string());
+    }
+    lines.join("\n")
+}
+
 async fn fetch_contract_spec(
     wasm_hash: &str,
     network: &network::Network,
@@ -1181,6 +2408,108 @@ async fn fetch_contract_spec(
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_values_stay_one_argument() {
+        let values = HashMap::from([
+            (
+                Reference::NetworkPassphrase,
+                "Test SDF Network ; September 2015".to_string(),
+            ),
+            (
+                Reference::AccountAddress("alice".to_string()),
+                "GALICE".to_string(),
+            ),
+        ]);
+        let (source, args) = Builder::parse_script_line(
+            "STELLAR_ACCOUNT=${accounts.alice.address} --network ${network.passphrase} --name \"a b\"",
+            &values,
+        )
+        .unwrap();
+        assert_eq!(source.as_deref(), Some("GALICE"));
+        assert_eq!(
+            args,
+            [
+                "--network",
+                "Test SDF Network ; September 2015",
+                "--name",
+                "a b"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn script_values_are_quoted_in_subcommands() {
+        let values = HashMap::from([(
+            Reference::NetworkPassphrase,
+            "Test SDF Network ; September 2015".to_string(),
+        )]);
+        let (_, args) =
+            Builder::parse_script_line("--words $(echo ${network.passphrase} | wc -w)", &values)
+                .unwrap();
+        assert_eq!(args, ["--words", "6"]);
+    }
+
+    fn accounts(toml: &str) -> Vec<env_toml::Account> {
+        #[derive(serde::Deserialize)]
+        struct Accounts {
+            accounts: Vec<env_toml::Account>,
+        }
+        toml::from_str::<Accounts>(toml).unwrap().accounts
+    }
+
+    #[test]
+    fn validate_accounts_checks_funders_signers_and_keys() {
+        let valid = accounts(
+            r#"
+            accounts = [
+                { name = "treasury" },
+                { name = "admin", funded_by = "treasury", starting_balance = 100 },
+                { name = "vault", funded_by = "treasury", signers = { admin = 1 } },
+            ]
+            "#,
+        );
+        assert!(validate_accounts(&valid).is_ok());
+
+        let funded_before_funder = accounts(
+            r#"
+            accounts = [
+                { name = "admin", funded_by = "treasury" },
+                { name = "treasury" },
+            ]
+            "#,
+        );
+        assert!(matches!(
+            validate_accounts(&funded_before_funder),
+            Err(Error::UnknownFunder { account, funder }) if account == "admin" && funder == "treasury"
+        ));
+
+        let unknown_signer = accounts(
+            r#"
+            accounts = [{ name = "vault", signers = { admin = 1 } }]
+            "#,
+        );
+        assert!(matches!(
+            validate_accounts(&unknown_signer),
+            Err(Error::UnknownSigner { account, signer }) if account == "vault" && signer == "admin"
+        ));
+
+        let seed_and_secret = accounts(
+            r#"
+            accounts = [{ name = "admin", seed = "admin", secret_env = "ADMIN_SECRET" }]
+            "#,
+        );
+        assert!(matches!(
+            validate_accounts(&seed_and_secret),
+            Err(Error::SeedAndSecretEnv(name)) if name == "admin"
+        ));
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use super::*;
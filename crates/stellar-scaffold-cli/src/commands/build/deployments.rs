@@ -0,0 +1,135 @@
+//! `deployments/<env>.json`: the contracts deployed to an environment, written after
+//! each deploy and meant to be committed, so teammates and CI share the same deployed
+//! addresses instead of each relying on their own contract aliases. Environments on a
+//! network run locally have none, as it is reset.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use stellar_scaffold_ext_types::DeployKind;
+
+use super::clients::ScaffoldEnv;
+
+pub const DEPLOYMENTS_DIR: &str = "deployments";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("⛔ ️reading {0:?}: {1}")]
+    Reading(PathBuf, std::io::Error),
+    #[error("⛔ ️parsing {0:?}: {1}")]
+    Parsing(PathBuf, serde_json::Error),
+    #[error("⛔ ️writing {0:?}: {1}")]
+    Writing(PathBuf, std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Network the contracts are deployed to. A manifest for another network, e.g. a
+    /// local network that was reset, is ignored.
+    pub network_passphrase: String,
+    pub contracts: BTreeMap<String, Deployment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Deployment {
+    pub id: String,
+    pub wasm_hash: String,
+    pub deploy_kind: DeployKind,
+    /// Hash of the transaction that deployed or upgraded the contract
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    /// Ledger that transaction was included in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ledger: Option<u32>,
+    /// Seconds since the Unix epoch
+    pub deployed_at: u64,
+}
+
+impl Manifest {
+    pub fn path(workspace_root: &Path, env: &ScaffoldEnv) -> PathBuf {
+        workspace_root
+            .join(DEPLOYMENTS_DIR)
+            .join(format!("{}.json", env.name()))
+    }
+
+    /// The manifest of `env`, if there is one for `network_passphrase`
+    pub fn read(
+        workspace_root: &Path,
+        env: &ScaffoldEnv,
+        network_passphrase: &str,
+    ) -> Result<Option<Self>, Error> {
+        let path = Self::path(workspace_root, env);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents =
+            std::fs::read_to_string(&path).map_err(|e| Error::Reading(path.clone(), e))?;
+        let manifest: Self =
+            serde_json::from_str(&contents).map_err(|e| Error::Parsing(path.clone(), e))?;
+        Ok((manifest.network_passphrase == network_passphrase).then_some(manifest))
+    }
+
+    /// Record the deployment of contract `name` in the manifest of `env`
+    pub fn record(
+        workspace_root: &Path,
+        env: &ScaffoldEnv,
+        network_passphrase: &str,
+        name: &str,
+        deployment: Deployment,
+    ) -> Result<(), Error> {
+        let mut manifest =
+            Self::read(workspace_root, env, network_passphrase)?.unwrap_or_else(|| Self {
+                network_passphrase: network_passphrase.to_string(),
+                contracts: BTreeMap::new(),
+            });
+        manifest.contracts.insert(name.to_string(), deployment);
+
+        let path = Self::path(workspace_root, env);
+        let json = serde_json::to_string_pretty(&manifest).expect("manifest is valid JSON");
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| Error::Writing(path.clone(), e))?;
+        }
+        std::fs::write(&path, json + "\n").map_err(|e| Error::Writing(path.clone(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(id: &str) -> Deployment {
+        Deployment {
+            id: id.to_string(),
+            wasm_hash: "aa".to_string(),
+            deploy_kind: DeployKind::Fresh,
+            tx_hash: Some("bb".to_string()),
+            ledger: Some(42),
+            deployed_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn records_deployments_per_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = ScaffoldEnv::development();
+        Manifest::record(dir.path(), &env, "local", "token", deployment("CTOKEN")).unwrap();
+        Manifest::record(dir.path(), &env, "local", "oracle", deployment("CORACLE")).unwrap();
+
+        let manifest = Manifest::read(dir.path(), &env, "local").unwrap().unwrap();
+        assert_eq!(manifest.contracts.len(), 2);
+        assert_eq!(manifest.contracts["token"], deployment("CTOKEN"));
+        assert!(
+            Manifest::read(dir.path(), &env, "testnet")
+                .unwrap()
+                .is_none()
+        );
+
+        // Deploying to another network starts a new manifest
+        Manifest::record(dir.path(), &env, "testnet", "token", deployment("CNEW")).unwrap();
+        let manifest = Manifest::read(dir.path(), &env, "testnet")
+            .unwrap()
+            .unwrap();
+        assert_eq!(manifest.contracts.len(), 1);
+        assert!(dir.path().join("deployments/development.json").exists());
+    }
+}
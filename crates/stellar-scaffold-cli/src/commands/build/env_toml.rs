@@ -16,10 +16,18 @@ pub enum Error {
     NoSettingsForCurrentEnv(String),
     #[error("⛔ ️reading environments.toml as a string: {0}")]
     ParsingString(#[from] std::io::Error),
+    #[error("⛔ ️environment {0:?} in environments.toml must be a table")]
+    NotATable(String),
+    #[error("⛔ ️`extends` of environment {0:?} must be the name of another environment")]
+    InvalidExtends(String),
+    #[error("⛔ ️environment {env:?} extends {parent:?}, which is not in environments.toml")]
+    MissingParent { env: String, parent: String },
+    #[error("⛔ ️environments.toml `extends` cycle: {}", .0.join(" -> "))]
+    ExtendsCycle(Vec<String>),
+    #[error("⛔ ️editing environments.toml: {0}")]
+    EditingToml(#[from] toml_edit::TomlError),
 }
 
-type Environments = Map<Box<str>, Environment>;
-
 /// A single extension entry parsed from `environments.toml`.
 ///
 /// Extensions are declared with two independent keys that can be used together
@@ -56,8 +64,20 @@ pub struct Environment {
     pub accounts: Option<Vec<Account>>,
     pub network: Network,
     pub contracts: Option<IndexMap<Box<str>, Contract>>,
+    /// Classic assets to issue, with their Stellar Asset Contracts
+    pub assets: Option<IndexMap<Box<str>, Asset>>,
     /// Extensions to invoke for this environment, in execution order.
     pub extensions: Vec<ExtensionEntry>,
+    /// Deploy contracts built from source. When `false`, every contract must have a
+    /// pinned `id`. Defaults to `false` for `staging`
+    /// and `production`, `true` otherwise.
+    pub deploy: bool,
+    /// Run contracts' `after_deploy` scripts once they are deployed or upgraded. Defaults
+    /// to `true` for `development` and `testing`, `false` otherwise.
+    pub after_deploy: bool,
+    /// Let generated contract clients connect to an RPC server over plain HTTP. Defaults
+    /// to `true` for `development` and `testing`, `false` otherwise.
+    pub allow_http: bool,
 }
 
 fn deserialize_accounts<'de, D>(deserializer: D) -> Result<Option<Vec<Account>>, D::Error>
@@ -79,11 +99,18 @@ impl<'de> Deserialize<'de> for Environment {
             accounts: Option<Vec<Account>>,
             network: Network,
             contracts: Option<Table>,
+            assets: Option<IndexMap<Box<str>, Asset>>,
             /// Ordered list of extension names to invoke.
             #[serde(default)]
             extensions: Vec<String>,
             /// Per-extension config tables, keyed by extension name.
             ext: Option<Table>,
+            #[serde(default = "default_deploy")]
+            deploy: bool,
+            #[serde(default)]
+            after_deploy: bool,
+            #[serde(default)]
+            allow_http: bool,
         }
 
         let helper = EnvironmentHelper::deserialize(deserializer)?;
@@ -108,11 +135,19 @@ impl<'de> Deserialize<'de> for Environment {
             accounts: helper.accounts,
             network: helper.network,
             contracts,
+            assets: helper.assets,
             extensions,
+            deploy: helper.deploy,
+            after_deploy: helper.after_deploy,
+            allow_http: helper.allow_http,
         })
     }
 }
 
+fn default_deploy() -> bool {
+    true
+}
+
 /// Zips the ordered `extensions` name list with the optional `ext` config
 /// tables into a single [`ExtensionEntry`] list.
 ///
@@ -159,15 +194,73 @@ pub struct Account {
     pub name: String,
     #[serde(default)]
     pub default: bool,
+
+    /// Seed to derive the account's key from, as with `stellar keys generate --seed`, so
+    /// it gets the same address on every machine
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<String>,
+
+    /// Environment variable holding the account's secret key (`S...`), for accounts that
+    /// already exist, e.g. on a staging network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_env: Option<String>,
+
+    /// Create the account on chain if it doesn't exist yet
+    #[serde(default = "default_fund")]
+    pub fund: bool,
+
+    /// Account listed before this one that creates it, for networks without friendbot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub funded_by: Option<String>,
+
+    /// XLM `funded_by` sends when creating the account. Defaults to 10,000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starting_balance: Option<u64>,
+
+    /// Accounts that can sign for this one, with their weights
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub signers: IndexMap<String, u8>,
+
+    /// Weights needed to sign low, medium and high threshold operations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<Thresholds>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Thresholds {
+    #[serde(default)]
+    pub low: u8,
+    #[serde(default)]
+    pub medium: u8,
+    #[serde(default)]
+    pub high: u8,
+}
+
+fn default_fund() -> bool {
+    true
+}
+
+impl Account {
+    /// An account with the default settings
+    pub fn new(name: String) -> Self {
+        Account {
+            name,
+            default: false,
+            seed: None,
+            secret_env: None,
+            fund: true,
+            funded_by: None,
+            starting_balance: None,
+            signers: IndexMap::new(),
+            thresholds: None,
+        }
+    }
 }
 
 impl From<AccountRepresentation> for Account {
     fn from(rep: AccountRepresentation) -> Self {
         match rep {
-            AccountRepresentation::Simple(name) => Account {
-                name,
-                default: false,
-            },
+            AccountRepresentation::Simple(name) => Account::new(name),
             AccountRepresentation::Detailed(account) => account,
         }
     }
@@ -186,6 +279,48 @@ pub struct Contract {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub constructor_args: Option<String>,
+
+    /// Fail the build if the contract's Wasm is larger than this many KB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wasm_size_kb: Option<f64>,
+
+    /// Deploy a Wasm published to the registry instead of a local build, given as
+    /// `[channel/]name[@version]`, e.g. `unverified/oracle@^1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm: Option<String>,
+
+    /// What to do when the contract is already deployed with a different Wasm
+    #[serde(default)]
+    pub upgrade: UpgradeStrategy,
+
+    /// Function that upgrades the contract in place, given the new Wasm hash.
+    /// Defaults to `upgrade`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_fn: Option<String>,
+
+    /// Arguments passed to `upgrade_fn` after the Wasm hash, e.g. `--operator ${accounts.admin.address}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_args: Option<String>,
+
+    /// Commands run after upgrading the contract in place, in the format of `after_deploy`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migrate: Option<String>,
+}
+
+/// How to handle a contract that is already deployed with a different Wasm
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpgradeStrategy {
+    /// Upgrade in place if both the deployed and the new Wasm have an upgrade function,
+    /// otherwise deploy a new contract
+    #[default]
+    Auto,
+    /// Always deploy a new contract
+    Redeploy,
+    /// Keep the deployed contract as it is
+    Never,
+    /// Upgrade in place, failing if that isn't possible
+    Fail,
 }
 
 impl Default for Contract {
@@ -195,6 +330,12 @@ impl Default for Contract {
             after_deploy: None,
             id: None,
             constructor_args: None,
+            max_wasm_size_kb: None,
+            wasm: None,
+            upgrade: UpgradeStrategy::default(),
+            upgrade_fn: None,
+            upgrade_args: None,
+            migrate: None,
         }
     }
 }
@@ -203,11 +344,56 @@ fn default_client() -> bool {
     true
 }
 
+/// A classic asset under `[<env>.assets.<name>]`, created along with its trustlines,
+/// balances and Stellar Asset Contract when the environment deploys its contracts
+#[derive(Debug, Deserialize, Clone)]
+pub struct Asset {
+    /// Asset code of up to 12 letters and digits, or `native` for XLM
+    pub code: String,
+
+    /// Account in `accounts` that issues the asset. Not used for `native`
+    #[serde(default)]
+    pub issuer: Option<String>,
+
+    /// Deploy the asset's Stellar Asset Contract
+    #[serde(default = "default_sac")]
+    pub sac: bool,
+
+    /// Generate a client for the Stellar Asset Contract
+    #[serde(default = "default_client")]
+    pub client: bool,
+
+    /// Accounts that trust the asset
+    #[serde(default)]
+    pub trustlines: Vec<String>,
+
+    /// Amount in whole units the issuer pays each account while it holds none. The
+    /// accounts trust the asset too
+    #[serde(default)]
+    pub balances: IndexMap<String, u64>,
+}
+
+fn default_sac() -> bool {
+    true
+}
+
 impl Environment {
     pub fn get(
         workspace_root: &Path,
         scaffold_env: &ScaffoldEnv,
     ) -> Result<Option<Environment>, Error> {
+        Self::get_table(workspace_root, scaffold_env)?
+            .map(|table| Environment::deserialize(toml::Value::Table(table)))
+            .transpose()
+            .map_err(Error::from)
+    }
+
+    /// The raw table of `scaffold_env` in `environments.toml`, with the tables of the
+    /// environments it `extends` merged in.
+    pub fn get_table(
+        workspace_root: &Path,
+        scaffold_env: &ScaffoldEnv,
+    ) -> Result<Option<Table>, Error> {
         let env_toml = workspace_root.join(ENV_FILE);
 
         if !env_toml.exists() {
@@ -215,12 +401,158 @@ impl Environment {
         }
 
         let toml_str = std::fs::read_to_string(env_toml)?;
-        let mut parsed_toml: Environments = toml::from_str(&toml_str)?;
-        let current_env = parsed_toml.remove(scaffold_env.to_string().as_str());
-        if current_env.is_none() {
-            return Err(Error::NoSettingsForCurrentEnv(scaffold_env.to_string()));
+        let environments: Table = toml::from_str(&toml_str)?;
+        let mut table = resolve_extends(&environments, scaffold_env.name())?;
+        // Defaults that depend on the environment's name
+        if !table.contains_key("deploy") {
+            let deploy = scaffold_env.deploys_by_default();
+            table.insert("deploy".to_string(), deploy.into());
+        }
+        if !table.contains_key("after_deploy") {
+            let after_deploy = scaffold_env.testing_or_development();
+            table.insert("after_deploy".to_string(), after_deploy.into());
+        }
+        if !table.contains_key("allow_http") {
+            let allow_http = scaffold_env.testing_or_development();
+            table.insert("allow_http".to_string(), allow_http.into());
+        }
+        Ok(Some(table))
+    }
+
+    /// Names of all environments in `environments.toml`
+    pub fn names(workspace_root: &Path) -> Result<Vec<ScaffoldEnv>, Error> {
+        let env_toml = workspace_root.join(ENV_FILE);
+
+        if !env_toml.exists() {
+            return Ok(vec![]);
+        }
+
+        let toml_str = std::fs::read_to_string(env_toml)?;
+        let environments: Table = toml::from_str(&toml_str)?;
+        Ok(environments
+            .keys()
+            .filter_map(|name| name.parse().ok())
+            .collect())
+    }
+}
+
+/// Set the `id` of contract `name` in environment `scaffold_env`, keeping the rest of
+/// `environments.toml` as written
+pub fn set_contract_id(
+    workspace_root: &Path,
+    scaffold_env: &ScaffoldEnv,
+    name: &str,
+    id: &str,
+) -> Result<(), Error> {
+    let path = workspace_root.join(ENV_FILE);
+    let mut doc: toml_edit::DocumentMut = std::fs::read_to_string(&path)?.parse()?;
+    let not_a_table = || Error::NotATable(scaffold_env.to_string());
+    let mut table = doc.as_table_mut() as &mut dyn toml_edit::TableLike;
+    for key in [scaffold_env.name(), "contracts", name] {
+        table = table
+            .entry(key)
+            .or_insert_with(|| {
+                let mut t = toml_edit::Table::new();
+                t.set_implicit(true);
+                toml_edit::Item::Table(t)
+            })
+            .as_table_like_mut()
+            .ok_or_else(not_a_table)?;
+    }
+    table.insert("id", toml_edit::value(id));
+    std::fs::write(path, doc.to_string())?;
+    Ok(())
+}
+
+/// Follow the `extends` chain of environment `name`, merging each parent's table under
+/// its child's so the child's values take precedence (see [`merge_environments`]).
+fn resolve_extends(environments: &Table, name: &str) -> Result<Table, Error> {
+    let mut chain: Vec<String> = Vec::new();
+    let mut tables = Vec::new();
+    let mut current = name.to_string();
+    loop {
+        if chain.contains(&current) {
+            chain.push(current);
+            return Err(Error::ExtendsCycle(chain));
         }
-        Ok(current_env)
+        let table = match (environments.get(&current), chain.last()) {
+            (Some(toml::Value::Table(table)), _) => table,
+            (Some(_), _) => return Err(Error::NotATable(current)),
+            (None, None) => return Err(Error::NoSettingsForCurrentEnv(current)),
+            (None, Some(env)) => {
+                return Err(Error::MissingParent {
+                    env: env.clone(),
+                    parent: current,
+                });
+            }
+        };
+        tables.push(table);
+        let parent = match table.get("extends") {
+            None => break,
+            Some(toml::Value::String(parent)) => parent.clone(),
+            Some(_) => return Err(Error::InvalidExtends(current)),
+        };
+        chain.push(current);
+        current = parent;
+    }
+
+    let mut resolved = Table::new();
+    for table in tables.into_iter().rev() {
+        merge_environments(&mut resolved, table.clone());
+    }
+    resolved.remove("extends");
+    Ok(resolved)
+}
+
+/// Merge the environment `overrides` into `base`. `accounts` are merged by name, a `network`
+/// replaces the one in `base` wholesale so settings of different networks are never mixed,
+/// and everything else is deep-merged with [`merge_tables`].
+fn merge_environments(base: &mut Table, mut overrides: Table) {
+    if let Some(network) = overrides.remove("network") {
+        base.insert("network".to_string(), network);
+    }
+    match (base.get_mut("accounts"), overrides.remove("accounts")) {
+        (Some(toml::Value::Array(existing)), Some(toml::Value::Array(accounts))) => {
+            for account in accounts {
+                let name = account_name(&account);
+                match existing
+                    .iter()
+                    .position(|e| name.is_some() && account_name(e) == name)
+                {
+                    Some(i) => existing[i] = account,
+                    None => existing.push(account),
+                }
+            }
+        }
+        (_, Some(accounts)) => {
+            base.insert("accounts".to_string(), accounts);
+        }
+        (_, None) => {}
+    }
+    merge_tables(base, overrides);
+}
+
+/// Name of an entry of `accounts`, either `"alice"` or `{ name = "alice", ... }`
+fn account_name(account: &toml::Value) -> Option<&str> {
+    match account {
+        toml::Value::String(name) => Some(name),
+        toml::Value::Table(table) => table.get("name").and_then(toml::Value::as_str),
+        _ => None,
+    }
+}
+
+/// Deep-merge `overrides` into `base`. Nested tables are merged key by key; any other
+/// value, including arrays, replaces the one in `base`.
+fn merge_tables(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        let value = match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(value)) => {
+                merge_tables(existing, value);
+                continue;
+            }
+            (_, value) => value,
+        };
+        base.insert(key, value);
     }
 }
 
@@ -240,6 +572,8 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    type Environments = Map<Box<str>, Environment>;
+
     /// Parse a TOML string that contains exactly one environment keyed by
     /// `"development"` and return its [`Environment`].
     fn parse_dev(toml: &str) -> Environment {
@@ -252,6 +586,42 @@ mod tests {
 name = "testnet"
 "#;
 
+    #[test]
+    fn detailed_accounts() {
+        let toml = format!(
+            r#"{NETWORK_STUB}
+[development]
+accounts = [
+    "alice",
+    {{ name = "treasury", secret_env = "TREASURY_SECRET", fund = false }},
+    {{ name = "bob", seed = "bob", funded_by = "treasury", starting_balance = 50 }},
+    {{ name = "vault", signers = {{ alice = 1, bob = 1 }}, thresholds = {{ medium = 2, high = 2 }} }},
+]
+"#
+        );
+
+        let accounts = parse_dev(&toml).accounts.unwrap();
+        let [alice, treasury, bob, vault] = accounts.as_slice() else {
+            panic!("expected 4 accounts");
+        };
+        assert!(alice.fund);
+        assert!(alice.seed.is_none() && alice.signers.is_empty());
+        assert_eq!(treasury.secret_env.as_deref(), Some("TREASURY_SECRET"));
+        assert!(!treasury.fund);
+        assert_eq!(bob.seed.as_deref(), Some("bob"));
+        assert_eq!(bob.funded_by.as_deref(), Some("treasury"));
+        assert_eq!(bob.starting_balance, Some(50));
+        assert_eq!(vault.signers["bob"], 1);
+        assert_eq!(
+            vault.thresholds,
+            Some(Thresholds {
+                low: 0,
+                medium: 2,
+                high: 2
+            })
+        );
+    }
+
     #[test]
     fn extensions_with_config() {
         let toml = format!(
@@ -321,6 +691,32 @@ extensions = []
         assert!(env.extensions.is_empty());
     }
 
+    #[test]
+    fn upgrade_settings() {
+        let toml = format!(
+            r#"{NETWORK_STUB}
+[development.contracts.token]
+upgrade = "fail"
+upgrade_fn = "migrate_to"
+upgrade_args = "--admin ${{accounts.admin.address}}"
+migrate = "migrate_storage"
+
+[development.contracts.oracle]
+"#
+        );
+        let contracts = parse_dev(&toml).contracts.unwrap();
+        let token = &contracts["token"];
+        assert_eq!(token.upgrade, UpgradeStrategy::Fail);
+        assert_eq!(token.upgrade_fn.as_deref(), Some("migrate_to"));
+        assert!(token.upgrade_args.is_some());
+        assert_eq!(token.migrate.as_deref(), Some("migrate_storage"));
+        assert_eq!(contracts["oracle"].upgrade, UpgradeStrategy::Auto);
+
+        let invalid =
+            format!("{NETWORK_STUB}\n[development.contracts.token]\nupgrade = \"sometimes\"\n");
+        assert!(toml::from_str::<Environments>(&invalid).is_err());
+    }
+
     #[test]
     fn extensions_empty_ext_table() {
         // An ext sub-table with no keys → config should be None (not Some({})).
@@ -359,4 +755,202 @@ some_key = "value"
         assert_eq!(env.extensions.len(), 1);
         assert_eq!(env.extensions[0].name, "reporter");
     }
+
+    fn resolve(toml: &str, name: &str) -> Result<Table, Error> {
+        resolve_extends(&toml::from_str(toml).expect("invalid TOML"), name)
+    }
+
+    #[test]
+    fn extends_deep_merges_parent() {
+        let toml = r#"
+[development]
+accounts = ["alice", "bob"]
+extensions = ["reporter"]
+
+[development.network]
+name = "local"
+run-locally = true
+
+[development.contracts.token]
+constructor_args = "--name Dev"
+
+[development.contracts.counter]
+client = false
+
+[development.ext.reporter]
+warn_size_kb = 128
+mode = "summary"
+
+[testing]
+extends = "development"
+accounts = ["carol"]
+
+[testing.contracts.token]
+constructor_args = "--name Test"
+
+[testing.ext.reporter]
+warn_size_kb = 64
+"#;
+        let table = resolve(toml, "testing").unwrap();
+        assert!(!table.contains_key("extends"));
+        let env = Environment::deserialize(toml::Value::Table(table)).unwrap();
+
+        let accounts = env.accounts.unwrap();
+        let names = accounts.iter().map(|a| a.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["alice", "bob", "carol"]);
+        assert_eq!(env.network.name.as_deref(), Some("local"));
+        assert!(env.network.run_locally);
+
+        let contracts = env.contracts.unwrap();
+        assert_eq!(
+            contracts["token"].constructor_args.as_deref(),
+            Some("--name Test")
+        );
+        assert!(!contracts["counter"].client);
+
+        assert_eq!(env.extensions[0].name, "reporter");
+        assert_eq!(
+            env.extensions[0].config,
+            Some(json!({ "warn_size_kb": 64, "mode": "summary" }))
+        );
+    }
+
+    #[test]
+    fn extends_merges_accounts_by_name_and_replaces_network() {
+        let toml = r#"
+[development]
+accounts = ["alice", { name = "bob", default = true }]
+
+[development.network]
+name = "local"
+run-locally = true
+
+[staging]
+extends = "development"
+accounts = [{ name = "bob" }, "carol"]
+
+[staging.network]
+rpc-url = "https://soroban-testnet.stellar.org"
+network-passphrase = "Test SDF Network ; September 2015"
+"#;
+        let table = resolve(toml, "staging").unwrap();
+        let env = Environment::deserialize(toml::Value::Table(table)).unwrap();
+
+        let accounts = env.accounts.unwrap();
+        let names = accounts.iter().map(|a| a.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["alice", "bob", "carol"]);
+        assert!(!accounts[1].default);
+
+        assert_eq!(env.network.name, None);
+        assert!(!env.network.run_locally);
+        assert_eq!(
+            env.network.rpc_url.as_deref(),
+            Some("https://soroban-testnet.stellar.org")
+        );
+    }
+
+    #[test]
+    fn extends_missing_parent() {
+        let toml = format!(
+            r#"{NETWORK_STUB}
+[development]
+extends = "base"
+"#
+        );
+        assert!(matches!(
+            resolve(&toml, "development"),
+            Err(Error::MissingParent { env, parent }) if env == "development" && parent == "base"
+        ));
+    }
+
+    #[test]
+    fn extends_cycle() {
+        let toml = r#"
+[development]
+extends = "staging"
+
+[staging]
+extends = "testing"
+
+[testing]
+extends = "development"
+"#;
+        assert!(matches!(
+            resolve(toml, "development"),
+            Err(Error::ExtendsCycle(chain))
+                if chain == ["development", "staging", "testing", "development"]
+        ));
+    }
+
+    #[test]
+    fn custom_environment_with_name_based_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(ENV_FILE),
+            r#"
+[preview.network]
+name = "testnet"
+
+[sandbox]
+allow_http = true
+after_deploy = true
+
+[sandbox.network]
+name = "local"
+
+[production.network]
+name = "mainnet"
+"#,
+        )
+        .unwrap();
+        let get = |name: &str| {
+            Environment::get(dir.path(), &name.parse().unwrap())
+                .unwrap()
+                .unwrap()
+        };
+
+        let preview = get("preview");
+        assert!(preview.deploy);
+        assert!(!preview.after_deploy);
+        assert!(!preview.allow_http);
+        let sandbox = get("sandbox");
+        assert!(sandbox.deploy);
+        assert!(sandbox.after_deploy);
+        assert!(sandbox.allow_http);
+        let production = get("production");
+        assert!(!production.deploy);
+        assert!(!production.after_deploy);
+        assert!(!production.allow_http);
+
+        let names = Environment::names(dir.path()).unwrap();
+        assert_eq!(names.len(), 3);
+    }
+
+    #[test]
+    fn set_contract_id_keeps_the_rest_of_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(ENV_FILE);
+        std::fs::write(
+            &path,
+            r#"# Mainnet
+[production.network]
+name = "mainnet"
+
+[production.contracts.token]
+constructor_args = "--admin ${accounts.admin.address}"
+"#,
+        )
+        .unwrap();
+        let production = "production".parse().unwrap();
+        set_contract_id(dir.path(), &production, "token", "CTOKEN").unwrap();
+        set_contract_id(dir.path(), &production, "oracle", "CORACLE").unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# Mainnet\n[production.network]"));
+        let production = Environment::get(dir.path(), &production).unwrap().unwrap();
+        let contracts = production.contracts.unwrap();
+        assert_eq!(contracts["token"].id.as_deref(), Some("CTOKEN"));
+        assert!(contracts["token"].constructor_args.is_some());
+        assert_eq!(contracts["oracle"].id.as_deref(), Some("CORACLE"));
+    }
 }
@@ -0,0 +1,135 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use cargo_metadata::Package;
+use ignore::WalkBuilder;
+use sha2::{Digest, Sha256};
+use stellar_cli::commands::contract::build;
+
+use super::Error;
+
+/// Fingerprint of everything that goes into building a contract, used to skip
+/// compiling contracts that haven't changed since their last build.
+///
+/// Covers the source of the package and of every local (path) package it depends on,
+/// the ids of its registry dependencies, the build arguments, which include the
+/// injected contract meta, and the [`workspace_inputs`] shared by every contract.
+pub struct Fingerprint {
+    path: PathBuf,
+    hash: String,
+}
+
+impl Fingerprint {
+    pub fn new(
+        p: &Package,
+        cmd: &build::Cmd,
+        workspace_inputs: &str,
+        fingerprint_dir: &Path,
+    ) -> Result<Self, Error> {
+        let mut hasher = Sha256::new();
+        hasher.update(workspace_inputs.as_bytes());
+        hasher.update([0]);
+        hasher.update(format!("{cmd:?}").as_bytes());
+        hasher.update([0]);
+        let manifest_path = p.manifest_path.clone().into_std_path_buf();
+        for dep in stellar_build::deps::all(&manifest_path)? {
+            hasher.update(dep.id.repr.as_bytes());
+            hasher.update([0]);
+            if dep.source.is_none()
+                && let Some(dir) = dep.manifest_path.parent()
+            {
+                hash_dir(&mut hasher, dir.as_std_path()).map_err(Error::Fingerprint)?;
+            }
+        }
+        Ok(Self {
+            path: fingerprint_dir.join(p.name.replace('-', "_")),
+            hash: hex::encode(hasher.finalize()),
+        })
+    }
+
+    /// Whether the last successful build had the same fingerprint
+    pub fn is_fresh(&self) -> bool {
+        fs::read_to_string(&self.path).is_ok_and(|hash| hash.trim() == self.hash)
+    }
+
+    /// Record a successful build
+    pub fn save(&self) -> Result<(), Error> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(Error::Fingerprint)?;
+        }
+        fs::File::create(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", self.hash))
+            .map_err(Error::Fingerprint)
+    }
+}
+
+/// Hash of the build inputs outside of the contracts' packages: the `[profile.*]` tables of
+/// the workspace manifest, cargo config files, `RUSTFLAGS`, the toolchain file and the
+/// `rustc` version
+pub fn workspace_inputs(workspace_root: &Path) -> Result<String, Error> {
+    let mut hasher = Sha256::new();
+    let manifest =
+        fs::read_to_string(workspace_root.join("Cargo.toml")).map_err(Error::Fingerprint)?;
+    if let Some(profiles) = manifest
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|manifest| manifest.get("profile").cloned())
+    {
+        hasher.update(profiles.to_string().as_bytes());
+    }
+    hasher.update([0]);
+    let config_files = workspace_root
+        .ancestors()
+        .flat_map(|dir| [dir.join(".cargo/config.toml"), dir.join(".cargo/config")]);
+    let toolchain_files = ["rust-toolchain.toml", "rust-toolchain"].map(|f| workspace_root.join(f));
+    for file in config_files.chain(toolchain_files) {
+        if let Ok(contents) = fs::read(&file) {
+            hasher.update(file.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(contents);
+            hasher.update([0]);
+        }
+    }
+    for var in ["RUSTFLAGS", "CARGO_ENCODED_RUSTFLAGS"] {
+        hasher.update(std::env::var(var).unwrap_or_default().as_bytes());
+        hasher.update([0]);
+    }
+    // Run in the workspace so rustup picks the toolchain the contracts are built with
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("-vV")
+        .current_dir(workspace_root)
+        .output()
+        .map_err(Error::Fingerprint)?;
+    hasher.update(version.stdout);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Hash the path and contents of every file in `dir`, skipping hidden and gitignored
+/// files and `target` directories
+fn hash_dir(hasher: &mut Sha256, dir: &Path) -> io::Result<()> {
+    let mut files = WalkBuilder::new(dir)
+        .filter_entry(|entry| entry.file_name() != "target")
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(ignore::DirEntry::into_path)
+        .collect::<Vec<_>>();
+    files.sort();
+    for file in files {
+        hasher.update(
+            file.strip_prefix(dir)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .as_bytes(),
+        );
+        hasher.update([0]);
+        hasher.update(fs::read(&file)?);
+        hasher.update([0]);
+    }
+    Ok(())
+}
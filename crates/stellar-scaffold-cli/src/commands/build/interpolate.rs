@@ -0,0 +1,147 @@
+//! `${...}` references in `constructor_args`, `after_deploy`, `upgrade_args` and
+//! `migrate`, resolved by scaffold itself rather than a shell:
+//!
+//! - `${contracts.<name>.id}`: ID of another contract in the environment
+//! - `${accounts.<name>.address}`: public key of an account in the environment
+//! - `${assets.<name>.id}`: ID of the Stellar Asset Contract of an asset in the environment
+//! - `${network.passphrase}` and `${network.rpc_url}`
+//! - `${env.<VAR>}`: an environment variable
+//!
+//! `${...}` that doesn't start with one of these namespaces is left as is, so shell
+//! variables inside `$(...)` keep working. Values are substituted into each argument after
+//! the line is split, and shell-quoted inside `$(...)`.
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+static REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\{([^}]*)\}").expect("Invalid regex pattern"));
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(
+        "⛔ ️Unknown reference ${{{0}}}. Expected contracts.<name>.id, accounts.<name>.address, assets.<name>.id, network.passphrase, network.rpc_url or env.<VAR>"
+    )]
+    Unknown(String),
+    #[error("⛔ ️${{{0}}} refers to a contract, account or asset that isn't in environments.toml")]
+    NotFound(String),
+    #[error("⛔ ️Unresolved reference ${{{0}}}")]
+    Unresolved(String),
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Reference {
+    ContractId(String),
+    AccountAddress(String),
+    AssetId(String),
+    NetworkPassphrase,
+    NetworkRpcUrl,
+    Env(String),
+}
+
+impl Reference {
+    fn parse(inner: &str) -> Result<Option<Self>, Error> {
+        let parts = inner.trim().split('.').collect::<Vec<_>>();
+        let reference = match parts.as_slice() {
+            ["contracts", name, "id"] => Self::ContractId((*name).to_string()),
+            ["accounts", name, "address"] => Self::AccountAddress((*name).to_string()),
+            ["assets", name, "id"] => Self::AssetId((*name).to_string()),
+            ["network", "passphrase"] => Self::NetworkPassphrase,
+            ["network", "rpc_url"] => Self::NetworkRpcUrl,
+            ["env", var] => Self::Env((*var).to_string()),
+            ["contracts" | "accounts" | "assets" | "network" | "env", ..] => {
+                return Err(Error::Unknown(inner.to_string()));
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(reference))
+    }
+}
+
+impl std::fmt::Display for Reference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ContractId(name) => write!(f, "contracts.{name}.id"),
+            Self::AccountAddress(name) => write!(f, "accounts.{name}.address"),
+            Self::AssetId(name) => write!(f, "assets.{name}.id"),
+            Self::NetworkPassphrase => write!(f, "network.passphrase"),
+            Self::NetworkRpcUrl => write!(f, "network.rpc_url"),
+            Self::Env(var) => write!(f, "env.{var}"),
+        }
+    }
+}
+
+/// All references in `script`, in order of appearance
+pub fn references(script: &str) -> Result<Vec<Reference>, Error> {
+    REFERENCE
+        .captures_iter(script)
+        .filter_map(|cap| Reference::parse(&cap[1]).transpose())
+        .collect()
+}
+
+/// Replace every reference in `script` with its value from `values`
+pub fn substitute(script: &str, values: &HashMap<Reference, String>) -> Result<String, Error> {
+    let mut result = String::new();
+    let mut last_match = 0;
+    for cap in REFERENCE.captures_iter(script) {
+        let Some(reference) = Reference::parse(&cap[1])? else {
+            continue;
+        };
+        let whole_match = cap.get(0).unwrap();
+        let value = values
+            .get(&reference)
+            .ok_or_else(|| Error::Unresolved(reference.to_string()))?;
+        result.push_str(&script[last_match..whole_match.start()]);
+        result.push_str(value);
+        last_match = whole_match.end();
+    }
+    result.push_str(&script[last_match..]);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_references_and_skips_shell_variables() {
+        let script = "--token ${contracts.token.id} --admin ${accounts.alice.address} --home $(echo ${HOME}) --net ${network.passphrase} --usdc ${assets.usdc.id}";
+        assert_eq!(
+            references(script).unwrap(),
+            vec![
+                Reference::ContractId("token".to_string()),
+                Reference::AccountAddress("alice".to_string()),
+                Reference::NetworkPassphrase,
+                Reference::AssetId("usdc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_reference_in_known_namespace() {
+        assert!(matches!(
+            references("--token ${contracts.token.address}"),
+            Err(Error::Unknown(r)) if r == "contracts.token.address"
+        ));
+    }
+
+    #[test]
+    fn substitutes_values() {
+        let values = HashMap::from([
+            (
+                Reference::ContractId("token".to_string()),
+                "CABC".to_string(),
+            ),
+            (Reference::Env("FEE".to_string()), "100".to_string()),
+        ]);
+        assert_eq!(
+            substitute(
+                "--token ${contracts.token.id} --fee ${ env.FEE } ${HOME}",
+                &values
+            )
+            .unwrap(),
+            "--token CABC --fee 100 ${HOME}"
+        );
+    }
+}
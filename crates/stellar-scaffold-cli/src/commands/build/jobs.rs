@@ -0,0 +1,283 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    env,
+    hash::Hash,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::mpsc,
+    thread,
+};
+
+use cargo_metadata::{Package, PackageId};
+use stellar_cli::{
+    commands::{contract::build, global},
+    print::Print,
+};
+
+use super::Error;
+
+/// Build packages with up to `jobs` concurrent `build-contract` runs of this executable,
+/// starting each package once all of its contract dependencies are built. Dependencies
+/// that aren't in `builds` are assumed to be built already.
+///
+/// Each job slot compiles in its own target dir under `target_dir`, so cargo's lock on
+/// the shared one doesn't serialize them. The Wasm is still written to each command's
+/// `out_dir`, and contract imports still resolve from the shared target dir. Output is captured and printed per package once it finishes, so it isn't
+/// interleaved. After a failure no new builds are started; builds already running are
+/// waited on.
+pub fn build(
+    builds: Vec<(Package, build::Cmd)>,
+    dependencies: &BTreeMap<PackageId, Vec<PackageId>>,
+    jobs: usize,
+    target_dir: &Path,
+    global_args: &global::Args,
+    printer: &Print,
+) -> Result<(), Error> {
+    let exe = env::current_exe().map_err(Error::BuildJob)?;
+    let names = builds
+        .iter()
+        .map(|(p, _)| (p.id.clone(), p.name.clone()))
+        .collect::<BTreeMap<_, _>>();
+    let tasks = builds
+        .into_iter()
+        .map(|(p, cmd)| (p.id, (p.name, cmd)))
+        .collect();
+    let unbuilt = schedule(tasks, dependencies, jobs, |slot, (name, cmd)| {
+        let output = Command::new(&exe)
+            .args(args(&cmd, global_args))
+            .env("CARGO_TARGET_DIR", job_target_dir(target_dir, slot))
+            .env(stellar_build::deps::TARGET_DIR_ENV, target_dir)
+            .output();
+        printer.infoln(format!("Building {name}"));
+        match output {
+            Ok(Output {
+                status,
+                stdout,
+                stderr,
+            }) => {
+                let _ = std::io::stderr().write_all(&stderr);
+                let _ = std::io::stdout().write_all(&stdout);
+                if status.success() {
+                    printer.checkln(format!("Built {name}"));
+                    Ok(())
+                } else {
+                    printer.errorln(format!("Building {name} failed"));
+                    Err(Error::Exit(status))
+                }
+            }
+            Err(e) => {
+                printer.errorln(format!("Building {name} failed"));
+                Err(Error::BuildJob(e))
+            }
+        }
+    })?;
+    if unbuilt.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::UnbuiltDependencies(
+            unbuilt.iter().map(|id| names[id].clone()).collect(),
+        ))
+    }
+}
+
+/// Target dir for the builds in job `slot`, kept between runs so later builds are
+/// incremental
+fn job_target_dir(target_dir: &Path, slot: usize) -> PathBuf {
+    target_dir
+        .join("stellar")
+        .join("jobs")
+        .join(slot.to_string())
+}
+
+/// Arguments for this executable's hidden `build-contract` command equivalent to
+/// running `cmd` with `global_args`
+fn args(cmd: &build::Cmd, global_args: &global::Args) -> Vec<String> {
+    let mut args = Vec::new();
+    if global_args.quiet {
+        args.push("--quiet".to_string());
+    }
+    if global_args.verbose {
+        args.push("--verbose".to_string());
+    }
+    if global_args.very_verbose {
+        args.push("--very-verbose".to_string());
+    }
+    args.extend([
+        "build-contract".to_string(),
+        "--profile".to_string(),
+        cmd.profile.clone(),
+    ]);
+    let mut push = |flag: &str, value: Option<String>| {
+        if let Some(value) = value {
+            args.push(flag.to_string());
+            args.push(value);
+        }
+    };
+    push(
+        "--manifest-path",
+        cmd.manifest_path.as_ref().map(|p| p.display().to_string()),
+    );
+    push("--package", cmd.package.clone());
+    push(
+        "--out-dir",
+        cmd.out_dir.as_ref().map(|p| p.display().to_string()),
+    );
+    push("--features", cmd.features.clone());
+    for (key, value) in &cmd.build_args.meta {
+        push("--meta", Some(format!("{key}={value}")));
+    }
+    if cmd.all_features {
+        args.push("--all-features".to_string());
+    }
+    if cmd.no_default_features {
+        args.push("--no-default-features".to_string());
+    }
+    if cmd.locked {
+        args.push("--locked".to_string());
+    }
+    if cmd.print_commands_only {
+        args.push("--print-commands-only".to_string());
+    }
+    args
+}
+
+/// Run each task on up to `jobs` threads once the tasks it depends on have succeeded,
+/// starting ready tasks in the order given. Each task is passed the job slot it runs in,
+/// below `jobs`, which no other running task is using. Returns the first error, or else the tasks
+/// which never became ready because they depend on each other.
+fn schedule<K, T, E>(
+    tasks: Vec<(K, T)>,
+    dependencies: &BTreeMap<K, Vec<K>>,
+    jobs: usize,
+    run: impl Fn(usize, T) -> Result<(), E> + Sync,
+) -> Result<Vec<K>, E>
+where
+    K: Clone + Eq + Hash + Ord + Send,
+    T: Send,
+    E: Send,
+{
+    let scheduled = tasks.iter().map(|(k, _)| k.clone()).collect::<HashSet<_>>();
+    let mut pending = tasks;
+    let mut done = HashSet::new();
+    let mut free = (0..jobs.max(1)).rev().collect::<Vec<_>>();
+    let mut failed = None;
+    let (tx, rx) = mpsc::channel();
+    let run = &run;
+
+    thread::scope(|scope| {
+        loop {
+            while failed.is_none() && !free.is_empty() {
+                let Some(i) = pending.iter().position(|(k, _)| {
+                    dependencies
+                        .get(k)
+                        .into_iter()
+                        .flatten()
+                        .all(|dep| done.contains(dep) || !scheduled.contains(dep))
+                }) else {
+                    break;
+                };
+                let (key, task) = pending.remove(i);
+                let slot = free.pop().expect("checked above");
+                let tx = tx.clone();
+                scope.spawn(move || {
+                    let _ = tx.send((key, slot, run(slot, task)));
+                });
+            }
+            if free.len() == jobs.max(1) {
+                break;
+            }
+
+            let (key, slot, result) = rx.recv().expect("build thread panicked");
+            free.push(slot);
+            match result {
+                Ok(()) => {
+                    done.insert(key);
+                }
+                Err(e) => {
+                    failed.get_or_insert(e);
+                }
+            }
+        }
+    });
+
+    match failed {
+        Some(e) => Err(e),
+        None => Ok(pending.into_iter().map(|(k, _)| k).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn deps(
+        edges: &[(&'static str, &[&'static str])],
+    ) -> BTreeMap<&'static str, Vec<&'static str>> {
+        edges.iter().map(|(k, deps)| (*k, deps.to_vec())).collect()
+    }
+
+    #[test]
+    fn dependencies_finish_first() {
+        let dependencies = deps(&[("app", &["oracle", "token"]), ("oracle", &["token"])]);
+        let tasks = ["token", "oracle", "app", "other"].map(|k| (k, k)).to_vec();
+        let order = Mutex::new(Vec::new());
+        let unbuilt = schedule(tasks, &dependencies, 4, |_, k| {
+            order.lock().unwrap().push(k);
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+        assert!(unbuilt.is_empty());
+        let order = order.into_inner().unwrap();
+        let position = |k| order.iter().position(|o| *o == k).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(position("token") < position("oracle"));
+        assert!(position("oracle") < position("app"));
+    }
+
+    #[test]
+    fn failures_stop_dependents() {
+        let dependencies = deps(&[("app", &["token"])]);
+        let tasks = ["token", "app"].map(|k| (k, k)).to_vec();
+        let started = Mutex::new(Vec::new());
+        let result = schedule(tasks, &dependencies, 2, |_, k| {
+            started.lock().unwrap().push(k);
+            if k == "token" { Err(k) } else { Ok(()) }
+        });
+        assert_eq!(result, Err("token"));
+        assert_eq!(started.into_inner().unwrap(), ["token"]);
+    }
+
+    #[test]
+    fn running_tasks_have_distinct_slots() {
+        let tasks = ["a", "b", "c", "d", "e"].map(|k| (k, k)).to_vec();
+        let running = Mutex::new(HashSet::new());
+        let unbuilt = schedule(tasks, &BTreeMap::new(), 2, |slot, _| {
+            assert!(slot < 2);
+            assert!(running.lock().unwrap().insert(slot));
+            thread::sleep(std::time::Duration::from_millis(5));
+            running.lock().unwrap().remove(&slot);
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+        assert!(unbuilt.is_empty());
+    }
+
+    #[test]
+    fn cycles_are_returned_unbuilt() {
+        let dependencies = deps(&[("a", &["b"]), ("b", &["a"])]);
+        let tasks = ["a", "b", "c"].map(|k| (k, k)).to_vec();
+        let unbuilt = schedule(tasks, &dependencies, 2, |_, _| Ok::<_, ()>(())).unwrap();
+        assert_eq!(unbuilt, ["a", "b"]);
+    }
+
+    #[test]
+    fn unscheduled_dependencies_are_already_built() {
+        let dependencies = deps(&[("app", &["token"])]);
+        let unbuilt =
+            schedule(vec![("app", ())], &dependencies, 1, |_, ()| Ok::<_, ()>(())).unwrap();
+        assert!(unbuilt.is_empty());
+    }
+}
@@ -3,9 +3,10 @@ use crate::commands::build::Error::EmptyPackageName;
 use crate::commands::version;
 use crate::extension;
 use cargo_metadata::camino::Utf8PathBuf;
-use cargo_metadata::{Metadata, MetadataCommand, Package};
+use cargo_metadata::{Metadata, MetadataCommand, Package, PackageId};
 use clap::Parser;
 use clients::ScaffoldEnv;
+use fingerprint::Fingerprint;
 use serde_json::Value;
 use std::collections::BTreeMap;
 use std::{fmt::Debug, io, path::Path, process::ExitStatus};
@@ -14,9 +15,16 @@ use stellar_cli::commands::{contract::build, global};
 use stellar_cli::print::Print;
 use stellar_scaffold_ext_types::{CompileContext, HookName};
 
+pub mod assets;
+pub mod budget;
 pub mod clients;
+pub mod deployments;
 pub mod docker;
 pub mod env_toml;
+pub mod fingerprint;
+pub mod interpolate;
+pub mod jobs;
+pub mod plan;
 
 /// Build a contract from source
 ///
@@ -32,11 +40,20 @@ pub struct Command {
     /// List package names in order of build
     #[arg(long, visible_alias = "ls")]
     pub list: bool,
+    /// Number of contracts to build at once. Contracts are only built once the contracts
+    /// they depend on are built. Each job compiles in its own target directory, so the
+    /// first parallel build compiles dependencies once per job
+    #[arg(long, short = 'j', default_value_t = 1)]
+    pub jobs: usize,
     #[command(flatten)]
     pub build: build::Cmd,
     /// Build client code in addition to building the contract
     #[arg(long)]
     pub build_clients: bool,
+    /// Also copy each built Wasm to `<name>_<x_y_z>.wasm`, using the crate version, so it can
+    /// be imported with `import_contract_client!("name@x.y.z")`
+    #[arg(long)]
+    pub snapshot: bool,
     #[command(flatten)]
     pub build_clients_args: clients::Args,
 }
@@ -51,6 +68,19 @@ pub enum Error {
     CargoCmd(io::Error),
     #[error("exit status {0}")]
     Exit(ExitStatus),
+    #[error("starting build job: {0}")]
+    BuildJob(io::Error),
+    #[error("computing build fingerprint: {0}")]
+    Fingerprint(io::Error),
+    #[error(
+        "Wasm size budget exceeded:\n{}",
+        .0.iter().map(|v| format!("  {v}")).collect::<Vec<_>>().join("\n")
+    )]
+    WasmSizeBudget(Vec<budget::Violation>),
+    #[error("recording Wasm size at {0:?}: {1}")]
+    WasmSizeRecord(std::path::PathBuf, io::Error),
+    #[error("could not build {}: their contract dependencies were never built", .0.join(", "))]
+    UnbuiltDependencies(Vec<String>),
     #[error("package {package} not found")]
     PackageNotFound { package: String },
     #[error("creating out directory: {0}")]
@@ -73,46 +103,61 @@ pub enum Error {
 
 impl Command {
     pub fn list_packages(&self, metadata: &Metadata) -> Result<Vec<Package>, Error> {
+        Ok(self.workspace(metadata)?.0)
+    }
+
+    /// Packages to build in dependency order, with the contract dependencies of each
+    fn workspace(
+        &self,
+        metadata: &Metadata,
+    ) -> Result<(Vec<Package>, BTreeMap<PackageId, Vec<PackageId>>), Error> {
         let packages = self.packages(metadata)?;
-        Ok(stellar_build::deps::get_workspace(&packages)?)
+        let dependencies = stellar_build::deps::contract_dependencies(&packages)?;
+        let packages = stellar_build::deps::sort_workspace(&packages, &dependencies)?;
+        Ok((packages, dependencies))
     }
 
     async fn start_local_docker_if_needed(
         &self,
         workspace_root: &Path,
         env: &ScaffoldEnv,
+        printer: &Print,
     ) -> Result<(), Error> {
         if let Some(current_env) = env_toml::Environment::get(workspace_root, env)?
             && current_env.network.run_locally
         {
+            printer.infoln("Starting local Stellar Docker container...");
             docker::start_local_stellar().await.map_err(|e| {
                 eprintln!("Failed to start Stellar Docker container: {e:?}");
                 Error::DockerStart
             })?;
+            printer.checkln("Local Stellar network is healthy and running.");
         }
         Ok(())
     }
 
-    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+    /// Build the contracts and, with `--build-clients`, deploy them and generate their
+    /// clients. Returns what happened to each contract, which is empty without
+    /// `--build-clients`.
+    pub async fn run(
+        &self,
+        global_args: &global::Args,
+    ) -> Result<Vec<clients::ContractReport>, Error> {
         let printer = Print::new(global_args.quiet);
         let metadata = self.metadata()?;
-        let packages = self.list_packages(&metadata)?;
+        let (packages, dependencies) = self.workspace(&metadata)?;
         let workspace_root = metadata.workspace_root.as_std_path();
 
-        if let Some(env) = &self.build_clients_args.env
-            && env == &ScaffoldEnv::Development
-        {
-            printer.infoln("Starting local Stellar Docker container...");
-            self.start_local_docker_if_needed(workspace_root, env)
+        if let Some(env) = &self.build_clients_args.env {
+            self.start_local_docker_if_needed(workspace_root, env, &printer)
                 .await?;
-            printer.checkln("Local Stellar network is healthy and running.");
         }
 
         if self.list {
             for p in packages {
                 println!("{}", p.name);
             }
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         let target_dir = &metadata.target_directory;
@@ -121,8 +166,10 @@ impl Command {
         let scaffold_env = self
             .build_clients_args
             .env
-            .unwrap_or(ScaffoldEnv::Development);
-        let extensions = match env_toml::Environment::get(workspace_root, &scaffold_env)? {
+            .clone()
+            .unwrap_or_else(ScaffoldEnv::development);
+        let env_config = env_toml::Environment::get(workspace_root, &scaffold_env)?;
+        let extensions = match &env_config {
             Some(env_config) if !env_config.extensions.is_empty() => {
                 extension::discover(&env_config.extensions, &printer)
             }
@@ -155,8 +202,65 @@ impl Command {
         )
         .await;
 
+        // Skip contracts whose sources, dependencies and build args haven't changed
+        let fingerprint_dir = stellar_build::deps::stellar_wasm_out_dir(target_dir.as_std_path())
+            .join(".fingerprint");
+        let workspace_inputs = fingerprint::workspace_inputs(workspace_root)?;
+        let mut builds = Vec::new();
         for p in &packages {
-            self.create_cmd(p, target_dir)?.run(global_args)?;
+            let cmd = self.create_cmd(p, target_dir)?;
+            let fingerprint = Fingerprint::new(p, &cmd, &workspace_inputs, &fingerprint_dir)?;
+            let wasm = wasm_out_dir
+                .join(p.name.replace('-', "_"))
+                .with_extension("wasm");
+            if !cmd.print_commands_only && wasm.exists() && fingerprint.is_fresh() {
+                printer.infoln(format!("Skipping {}, unchanged since last build", p.name));
+            } else {
+                builds.push((p.clone(), cmd, fingerprint));
+            }
+        }
+
+        if self.jobs > 1 && builds.len() > 1 {
+            let cmds = builds
+                .iter()
+                .map(|(p, cmd, _)| (p.clone(), cmd.clone()))
+                .collect();
+            jobs::build(
+                cmds,
+                &dependencies,
+                self.jobs,
+                target_dir.as_std_path(),
+                global_args,
+                &printer,
+            )?;
+        } else {
+            for (_, cmd, _) in &builds {
+                cmd.run(global_args)?;
+            }
+        }
+        if !self.build.print_commands_only {
+            for (_, _, fingerprint) in &builds {
+                fingerprint.save()?;
+            }
+            budget::check(
+                &packages,
+                env_config.as_ref(),
+                &wasm_out_dir,
+                &fingerprint_dir,
+            )?;
+        }
+
+        if self.snapshot && !self.build.print_commands_only {
+            for p in &packages {
+                let name = p.name.replace('-', "_");
+                let wasm = wasm_out_dir.join(&name).with_extension("wasm");
+                let version = p.version.to_string().replace('.', "_");
+                let snapshot = wasm_out_dir
+                    .join(format!("{name}_{version}"))
+                    .with_extension("wasm");
+                std::fs::copy(&wasm, &snapshot).map_err(Error::CopyingWasmFile)?;
+                printer.infoln(format!("Snapshot of {} at {}", p.name, snapshot.display()));
+            }
         }
 
         // Build post-compile context with populated wasm_paths.
@@ -193,12 +297,12 @@ impl Command {
             build_clients_args.global_args = Some(global_args.clone());
             build_clients_args.extensions = extensions;
             build_clients_args.compile_ctx = Some(post_compile_ctx);
-            build_clients_args
+            return Ok(build_clients_args
                 .run(packages.iter().map(|p| p.name.replace('-', "_")).collect())
-                .await?;
+                .await?);
         }
 
-        Ok(())
+        Ok(Vec::new())
     }
 
     fn packages(&self, metadata: &Metadata) -> Result<Vec<Package>, Error> {
@@ -289,6 +393,7 @@ impl Command {
             meta_map.remove("rsver");
             meta_map.remove("rssdkver");
             meta_map.remove("cargo_inherit");
+            meta_map.remove(budget::MAX_WASM_SIZE_KB);
             // Rename some fields
             if let Some(version) = meta_map.remove("version") {
                 meta_map.insert("binver".to_string(), version);
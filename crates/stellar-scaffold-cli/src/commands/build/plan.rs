@@ -0,0 +1,140 @@
+//! Deployment plans, written by `stellar scaffold deploy --plan` and executed as is by
+//! `stellar scaffold deploy --apply`.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plan {
+    /// Environment in `environments.toml` the plan was made for
+    pub env: String,
+    pub network_passphrase: String,
+    /// In the order they will be deployed
+    pub contracts: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    pub name: String,
+    /// Hash of the Wasm the contract will run
+    pub wasm_hash: String,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum Action {
+    /// Deploy a new contract, in place of the pinned contract `replaces` if any
+    Fresh {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        replaces: Option<String>,
+    },
+    /// Upgrade contract `id` in place from `current_wasm_hash`
+    Upgrade {
+        id: String,
+        current_wasm_hash: String,
+    },
+    /// Contract `id` already runs the Wasm
+    Unchanged { id: String },
+    /// Contract `id` runs another Wasm, `wasm_hash`, and is kept as is per its upgrade
+    /// strategy
+    Kept { id: String },
+}
+
+impl Plan {
+    /// Whether applying the plan changes anything on chain
+    pub fn has_changes(&self) -> bool {
+        self.contracts
+            .iter()
+            .any(|step| !matches!(step.action, Action::Unchanged { .. } | Action::Kept { .. }))
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = &self.name;
+        match &self.action {
+            Action::Fresh { replaces: None } => write!(f, "{name}: deploy a new contract"),
+            Action::Fresh { replaces: Some(id) } => {
+                write!(f, "{name}: deploy a new contract, replaces {id}")
+            }
+            Action::Upgrade { id, .. } => write!(f, "{name}: upgrade {id}"),
+            Action::Unchanged { id } => write!(f, "{name}: unchanged ({id})"),
+            Action::Kept { id } => write!(f, "{name}: kept at its current Wasm ({id})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_json_round_trip() {
+        let plan = Plan {
+            env: "production".to_string(),
+            network_passphrase: "Public Global Stellar Network ; September 2015".to_string(),
+            contracts: vec![
+                Step {
+                    name: "token".to_string(),
+                    wasm_hash: "aa".to_string(),
+                    action: Action::Upgrade {
+                        id: "CTOKEN".to_string(),
+                        current_wasm_hash: "bb".to_string(),
+                    },
+                },
+                Step {
+                    name: "oracle".to_string(),
+                    wasm_hash: "cc".to_string(),
+                    action: Action::Fresh { replaces: None },
+                },
+                Step {
+                    name: "vault".to_string(),
+                    wasm_hash: "dd".to_string(),
+                    action: Action::Fresh {
+                        replaces: Some("CVAULT".to_string()),
+                    },
+                },
+            ],
+        };
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(
+            json["contracts"][0],
+            serde_json::json!({
+                "name": "token",
+                "wasm_hash": "aa",
+                "action": "upgrade",
+                "id": "CTOKEN",
+                "current_wasm_hash": "bb",
+            })
+        );
+        assert_eq!(
+            json["contracts"][1],
+            serde_json::json!({ "name": "oracle", "wasm_hash": "cc", "action": "fresh" })
+        );
+        assert_eq!(json["contracts"][2]["replaces"], "CVAULT");
+        assert_eq!(serde_json::from_value::<Plan>(json).unwrap(), plan);
+        assert!(plan.has_changes());
+        assert_eq!(plan.contracts[0].to_string(), "token: upgrade CTOKEN");
+        assert_eq!(
+            plan.contracts[2].to_string(),
+            "vault: deploy a new contract, replaces CVAULT"
+        );
+        let kept = Step {
+            name: "vault".to_string(),
+            wasm_hash: "ee".to_string(),
+            action: Action::Kept {
+                id: "CVAULT".to_string(),
+            },
+        };
+        assert_eq!(serde_json::to_value(&kept).unwrap()["action"], "kept");
+        assert!(
+            !Plan {
+                contracts: vec![kept],
+                ..plan
+            }
+            .has_changes()
+        );
+    }
+}
@@ -108,7 +108,7 @@ impl Cmd {
     }
 
     fn clean_contract_aliases(workspace_root: &Path, printer: &Print) -> Result<(), Error> {
-        match Environment::get(workspace_root, &ScaffoldEnv::Development) {
+        match Environment::get(workspace_root, &ScaffoldEnv::development()) {
             Ok(Some(env)) => {
                 let network_args = Self::get_network_args(&env)?;
                 if let Some(contracts) = &env.contracts {
@@ -151,7 +151,7 @@ impl Cmd {
     }
 
     fn clean_identities(workspace_root: &Path, printer: &Print) {
-        match Environment::get(workspace_root, &ScaffoldEnv::Development) {
+        match Environment::get(workspace_root, &ScaffoldEnv::development()) {
             Ok(Some(env)) => {
                 // only clean the alias if it is only configured for Development, otherwise warn
                 if let Some(accounts) = &env.accounts {
@@ -196,48 +196,18 @@ impl Cmd {
     }
 
     fn account_in_other_envs(workspace_root: &Path, current_account: &Account) -> Vec<ScaffoldEnv> {
-        let mut other_envs: Vec<ScaffoldEnv> = vec![];
-
-        if let Some(testing) = Environment::get(workspace_root, &ScaffoldEnv::Testing)
-            .ok()
-            .flatten()
-        {
-            let found = testing
-                .accounts
-                .as_ref()
-                .is_some_and(|accts| accts.iter().any(|acct| acct.name == current_account.name));
-            if found {
-                other_envs.push(ScaffoldEnv::Testing);
-            }
-        }
-
-        if let Some(staging) = Environment::get(workspace_root, &ScaffoldEnv::Staging)
-            .ok()
-            .flatten()
-        {
-            let found = staging
-                .accounts
-                .as_ref()
-                .is_some_and(|accts| accts.iter().any(|acct| acct.name == current_account.name));
-            if found {
-                other_envs.push(ScaffoldEnv::Staging);
-            }
-        }
-
-        if let Some(production) = Environment::get(workspace_root, &ScaffoldEnv::Production)
-            .ok()
-            .flatten()
-        {
-            let found = production
-                .accounts
-                .as_ref()
-                .is_some_and(|accts| accts.iter().any(|acct| acct.name == current_account.name));
-            if found {
-                other_envs.push(ScaffoldEnv::Production);
-            }
-        }
-
-        other_envs
+        Environment::names(workspace_root)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|name| name != &ScaffoldEnv::development())
+            .filter(|name| {
+                Environment::get(workspace_root, name)
+                    .ok()
+                    .flatten()
+                    .and_then(|env| env.accounts)
+                    .is_some_and(|accts| accts.iter().any(|acct| acct.name == current_account.name))
+            })
+            .collect()
     }
 
     fn get_network_args(env: &Environment) -> Result<Vec<&str>, Error> {
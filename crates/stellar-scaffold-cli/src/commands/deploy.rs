@@ -0,0 +1,215 @@
+use std::{fs, io, path::PathBuf};
+
+use cargo_metadata::MetadataCommand;
+use clap::Parser;
+use dialoguer::Confirm;
+use stellar_cli::{commands::global, print::Print};
+
+use crate::commands::build::{
+    clients::{self, ScaffoldEnv},
+    env_toml,
+    plan::Plan,
+};
+use crate::extension;
+
+/// Deploy the contracts of an environment that `build` doesn't deploy to, like `staging`
+/// or `production`, in two steps: `--plan` works out what deploying each contract would
+/// do and writes it to a file, then `--apply` executes exactly that plan.
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Environment in `environments.toml` to plan for, defaulting to `STELLAR_SCAFFOLD_ENV`
+    /// with `--plan`; taken from the plan with `--apply`
+    #[arg(long)]
+    pub env: Option<ScaffoldEnv>,
+    /// Write what deploying each contract would do to this file, without changing
+    /// anything on chain
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = "deploy-plan.json",
+        required_unless_present = "apply",
+        conflicts_with = "apply"
+    )]
+    pub plan: Option<PathBuf>,
+    /// Execute a plan written by `--plan`, then write the ID of each contract to
+    /// `environments.toml`
+    #[arg(long, value_name = "FILE")]
+    pub apply: Option<PathBuf>,
+    /// Apply the plan without asking for confirmation
+    #[arg(long, short = 'y', requires = "apply")]
+    pub yes: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Metadata(#[from] cargo_metadata::Error),
+    #[error(transparent)]
+    EnvironmentsToml(#[from] env_toml::Error),
+    #[error(transparent)]
+    Clients(#[from] clients::Error),
+    #[error("⛔ ️no environments.toml with settings for {0:?} found")]
+    NoEnvironment(String),
+    #[error("⛔ ️reading plan {0:?}: {1}")]
+    ReadingPlan(PathBuf, io::Error),
+    #[error("⛔ ️writing plan {0:?}: {1}")]
+    WritingPlan(PathBuf, io::Error),
+    #[error("⛔ ️invalid plan {0:?}: {1}")]
+    InvalidPlan(PathBuf, serde_json::Error),
+    #[error("⛔ ️--plan needs an environment: pass --env or set STELLAR_SCAFFOLD_ENV")]
+    MissingEnv,
+    #[error("⛔ ️the plan is for environment {plan:?}, not {env:?}")]
+    EnvMismatch { plan: String, env: String },
+    #[error(transparent)]
+    Dialoguer(#[from] dialoguer::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let printer = Print::new(global_args.quiet);
+        let metadata = MetadataCommand::new().no_deps().exec()?;
+        let workspace_root = metadata.workspace_root.into_std_path_buf();
+
+        if let Some(path) = &self.apply {
+            let contents =
+                fs::read_to_string(path).map_err(|e| Error::ReadingPlan(path.clone(), e))?;
+            let plan: Plan =
+                serde_json::from_str(&contents).map_err(|e| Error::InvalidPlan(path.clone(), e))?;
+            let env: ScaffoldEnv = plan
+                .env
+                .parse()
+                .map_err(|_| Error::NoEnvironment(plan.env.clone()))?;
+            if let Some(requested) = &self.env
+                && requested != &env
+            {
+                return Err(Error::EnvMismatch {
+                    plan: plan.env.clone(),
+                    env: requested.to_string(),
+                });
+            }
+            self.apply(&workspace_root, &env, &plan, global_args, &printer)
+                .await
+        } else {
+            let env = self.plan_env()?;
+            let path = self.plan.clone().unwrap_or_default();
+            let builder = builder(&workspace_root, &env, global_args, &printer)?;
+            let plan = builder.plan().await?;
+            let json = serde_json::to_string_pretty(&plan).map_err(clients::Error::from)?;
+            fs::write(&path, json + "\n").map_err(|e| Error::WritingPlan(path.clone(), e))?;
+
+            print_plan(&plan, &printer);
+            if plan.has_changes() {
+                printer.checkln(format!(
+                    "Plan written to {}. Apply it with `stellar scaffold deploy --apply {}`",
+                    path.display(),
+                    path.display()
+                ));
+            } else {
+                printer.checkln(format!(
+                    "Every contract in {env} is up to date. Plan written to {}",
+                    path.display()
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    /// The environment to plan for: `--env`, or else `STELLAR_SCAFFOLD_ENV`. The variable
+    /// is only read here so that it can't conflict with the environment of an `--apply`ed plan
+    fn plan_env(&self) -> Result<ScaffoldEnv, Error> {
+        if let Some(env) = &self.env {
+            return Ok(env.clone());
+        }
+        let name = std::env::var("STELLAR_SCAFFOLD_ENV").map_err(|_| Error::MissingEnv)?;
+        name.parse().map_err(|_| Error::MissingEnv)
+    }
+
+    async fn apply(
+        &self,
+        workspace_root: &std::path::Path,
+        env: &ScaffoldEnv,
+        plan: &Plan,
+        global_args: &global::Args,
+        printer: &Print,
+    ) -> Result<(), Error> {
+        print_plan(plan, printer);
+        if !plan.has_changes() {
+            printer.checkln(format!("Nothing to apply to {env}"));
+            return Ok(());
+        }
+        if !self.yes
+            && !Confirm::new()
+                .with_prompt(format!("Apply this plan to {env}?"))
+                .default(false)
+                .interact()?
+        {
+            printer.warnln("Plan not applied");
+            return Ok(());
+        }
+
+        let builder = builder(workspace_root, env, global_args, printer)?;
+        let ids = builder.apply(plan).await?;
+
+        let contracts = env_toml::Environment::get(workspace_root, env)?
+            .and_then(|env| env.contracts)
+            .unwrap_or_default();
+        for (name, id) in ids {
+            let id = id.to_string();
+            let pinned = contracts
+                .get(name.as_str())
+                .and_then(|settings| settings.id.as_deref());
+            if pinned != Some(id.as_str()) {
+                env_toml::set_contract_id(workspace_root, env, &name, &id)?;
+                printer.infoln(format!("Set {env}.contracts.{name}.id = {id:?}"));
+            }
+        }
+        printer.checkln(format!("Plan applied to {env}"));
+        Ok(())
+    }
+}
+
+fn builder(
+    workspace_root: &std::path::Path,
+    env: &ScaffoldEnv,
+    global_args: &global::Args,
+    printer: &Print,
+) -> Result<clients::Builder, Error> {
+    let env_config = env_toml::Environment::get(workspace_root, env)?
+        .ok_or_else(|| Error::NoEnvironment(env.to_string()))?;
+    let args = clients::Args {
+        env: Some(env.clone()),
+        workspace_root: Some(workspace_root.to_path_buf()),
+        out_dir: None,
+        global_args: Some(global_args.clone()),
+        extensions: extension::discover(&env_config.extensions, printer),
+        compile_ctx: None,
+        fail_fast: false,
+        keep_going: false,
+    };
+    Ok(args.builder()?)
+}
+
+fn print_plan(plan: &Plan, printer: &Print) {
+    printer.infoln(format!("Deploy plan for {}:", plan.env));
+    for step in &plan.contracts {
+        printer.blankln(format!("  {step}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_or_apply_is_required() {
+        assert!(Cmd::try_parse_from(["deploy"]).is_err());
+        let cmd = Cmd::try_parse_from(["deploy", "--env", "production", "--plan"]).unwrap();
+        assert_eq!(cmd.plan, Some(PathBuf::from("deploy-plan.json")));
+        assert_eq!(
+            cmd.env.map(|env| env.to_string()).as_deref(),
+            Some("production")
+        );
+        assert!(Cmd::try_parse_from(["deploy", "--apply", "plan.json"]).is_ok());
+    }
+}
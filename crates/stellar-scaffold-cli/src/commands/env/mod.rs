@@ -0,0 +1,22 @@
+use clap::Parser;
+
+pub mod show;
+
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    #[command(subcommand)]
+    pub cmd: Command,
+}
+
+#[derive(Parser, Debug)]
+pub enum Command {
+    /// Print the fully resolved `environments.toml` config of an environment, with
+    /// every environment it `extends` merged in.
+    Show(show::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Show(#[from] show::Error),
+}
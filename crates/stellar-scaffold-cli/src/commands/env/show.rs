@@ -0,0 +1,44 @@
+use cargo_metadata::MetadataCommand;
+use clap::Parser;
+use serde::Deserialize;
+use toml::value::Table;
+
+use crate::commands::build::clients::ScaffoldEnv;
+use crate::commands::build::env_toml;
+
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    /// Scaffold environment to show.
+    #[arg(env = "STELLAR_SCAFFOLD_ENV", default_value = "development")]
+    pub env: ScaffoldEnv,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Metadata(#[from] cargo_metadata::Error),
+    #[error(transparent)]
+    Env(#[from] env_toml::Error),
+    #[error("⛔ ️no environments.toml found in {0}")]
+    NoEnvironmentsToml(String),
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let metadata = MetadataCommand::new().no_deps().exec()?;
+        let workspace_root = metadata.workspace_root.as_std_path();
+
+        let table = env_toml::Environment::get_table(workspace_root, &self.env)?
+            .ok_or_else(|| Error::NoEnvironmentsToml(workspace_root.display().to_string()))?;
+        // Fail on anything the build would reject, not just an invalid `extends` chain
+        env_toml::Environment::deserialize(toml::Value::Table(table.clone()))
+            .map_err(env_toml::Error::from)?;
+
+        let mut resolved = Table::new();
+        resolved.insert(self.env.to_string(), toml::Value::Table(table));
+        print!("{}", toml::to_string_pretty(&resolved)?);
+        Ok(())
+    }
+}
@@ -14,11 +14,7 @@ const H_HOOKS: &str = "HOOKS";
 #[derive(Parser, Debug)]
 pub struct Cmd {
     /// Scaffold environment whose extension list to inspect.
-    #[arg(
-        env = "STELLAR_SCAFFOLD_ENV",
-        value_enum,
-        default_value = "development"
-    )]
+    #[arg(env = "STELLAR_SCAFFOLD_ENV", default_value = "development")]
     pub env: ScaffoldEnv,
 }
 
@@ -39,7 +35,7 @@ impl Cmd {
 
         let Some(current_env) = env_toml::Environment::get(workspace_root, &self.env)? else {
             printer.warnln(format!(
-                "No environments.toml found or no {:?} environment configured.",
+                "No environments.toml found or no {} environment configured.",
                 self.env
             ));
             return Ok(());
@@ -47,7 +43,7 @@ impl Cmd {
 
         if current_env.extensions.is_empty() {
             printer.infoln(format!(
-                "No extensions configured for the {:?} environment.",
+                "No extensions configured for the {} environment.",
                 self.env
             ));
             return Ok(());
@@ -12,6 +12,8 @@ use stellar_cli;
 
 pub mod build;
 pub mod clean;
+pub mod deploy;
+pub mod env;
 pub mod ext;
 pub mod generate;
 pub mod init;
@@ -54,10 +56,16 @@ impl Root {
         match &mut self.cmd {
             Cmd::Init(init_info) => init_info.run(&self.global_args).await?,
             Cmd::Version(version_info) => version_info.run(),
-            Cmd::Build(build_info) => build_info.run(&self.global_args).await?,
+            Cmd::Build(build_info) => {
+                build_info.run(&self.global_args).await?;
+            }
+            Cmd::Deploy(deploy) => deploy.run(&self.global_args).await?,
             Cmd::Generate(generate) => match &mut generate.cmd {
                 generate::Command::Contract(contract) => contract.run(&self.global_args).await?,
             },
+            Cmd::Env(env_cmd) => match &env_cmd.cmd {
+                env::Command::Show(show) => show.run().map_err(env::Error::from)?,
+            },
             Cmd::Ext(ext_cmd) => match &ext_cmd.cmd {
                 ext::Command::Ls(ls) => ls.run(&self.global_args).map_err(ext::Error::from)?,
             },
@@ -65,6 +73,11 @@ impl Root {
             Cmd::UpdateEnv(e) => e.run()?,
             Cmd::Watch(watch_info) => watch_info.run(&self.global_args).await?,
             Cmd::Clean(clean) => clean.run(&self.global_args)?,
+            Cmd::BuildContract(build_cmd) => {
+                build_cmd
+                    .run(&self.global_args)
+                    .map_err(build::Error::from)?;
+            }
         }
         Ok(())
     }
@@ -88,9 +101,15 @@ pub enum Cmd {
     /// Build contracts, resolving dependencies in the correct order. If you have an `environments.toml` file, it will also follow its instructions to configure the environment set by the `STELLAR_SCAFFOLD_ENV` environment variable, turning your contracts into frontend packages (JS dependencies).
     Build(build::Command),
 
+    /// Plan deployments of the contracts in an environment like staging or production, then apply the plan and write the contract IDs to `environments.toml`
+    Deploy(deploy::Cmd),
+
     /// generate contracts
     Generate(generate::Cmd),
 
+    /// Inspect environments.toml
+    Env(env::Cmd),
+
     /// Inspect and manage extensions
     Ext(ext::Cmd),
 
@@ -105,6 +124,10 @@ pub enum Cmd {
 
     /// Clean Scaffold-generated artifacts from the given workspace
     Clean(clean::Cmd),
+
+    /// Build a single contract, as run by each job of `build --jobs`
+    #[command(hide = true)]
+    BuildContract(stellar_cli::commands::contract::build::Cmd),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -115,8 +138,12 @@ pub enum Error {
     #[error(transparent)]
     BuildContracts(#[from] build::Error),
     #[error(transparent)]
+    Deploy(#[from] deploy::Error),
+    #[error(transparent)]
     Contract(#[from] generate::contract::Error),
     #[error(transparent)]
+    Env(#[from] env::Error),
+    #[error(transparent)]
     Ext(#[from] ext::Error),
     #[error(transparent)]
     Upgrade(#[from] upgrade::Error),
@@ -218,8 +218,8 @@ impl Cmd {
 
         let env_config = Environment {
             accounts: Some(vec![Account {
-                name: "default".to_string(),
                 default: true,
+                ..Account::new("default".to_string())
             }]),
             network: Network {
                 name: None,
@@ -229,7 +229,11 @@ impl Cmd {
                 run_locally: true,
             },
             contracts: (!contract_configs.is_empty()).then_some(contract_configs),
+            assets: None,
             extensions: vec![],
+            deploy: true,
+            after_deploy: true,
+            allow_http: true,
         };
 
         let mut doc = DocumentMut::new();
@@ -336,12 +340,14 @@ impl Cmd {
         // Run scaffold build to generate WASM files
         let build_cmd = build::Command {
             build_clients_args: build::clients::Args {
-                env: Some(build::clients::ScaffoldEnv::Development),
+                env: Some(build::clients::ScaffoldEnv::development()),
                 workspace_root: Some(self.workspace_path.clone()),
                 out_dir: None,
                 global_args: Some(global_args.clone()),
                 extensions: vec![],
                 compile_ctx: None,
+                fail_fast: false,
+                keep_going: false,
             },
             build: stellar_cli::commands::contract::build::Cmd {
                 manifest_path: None,
@@ -356,7 +362,9 @@ impl Cmd {
                 build_args: stellar_cli::commands::contract::build::BuildArgs::default(),
             },
             list: false,
+            jobs: 1,
             build_clients: false, // Don't build clients, just contracts
+            snapshot: false,
         };
 
         build_cmd.run(global_args).await?;
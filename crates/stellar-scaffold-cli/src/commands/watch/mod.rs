@@ -11,7 +11,11 @@ use tokio::sync::Mutex;
 use tokio::sync::mpsc;
 use tokio::time;
 
-use crate::commands::build::{self, env_toml};
+use crate::commands::build::{
+    self,
+    clients::{self, ContractReport},
+    env_toml,
+};
 use crate::extension;
 use stellar_scaffold_ext_types::{HookName, ProjectContext, ProjectContractInfo};
 
@@ -139,6 +143,7 @@ impl Cmd {
         let printer = Print::new(global_args.quiet);
         let (tx, mut rx) = mpsc::channel::<Message>(100);
         let rebuild_state = Arc::new(Mutex::new(false));
+        let reports = Arc::new(Mutex::new(Vec::new()));
         let metadata = &self.build_cmd.metadata()?;
         let workspace_root = metadata.workspace_root.as_std_path();
 
@@ -146,7 +151,8 @@ impl Cmd {
             .build_cmd
             .build_clients_args
             .env
-            .unwrap_or(ScaffoldEnv::Development);
+            .clone()
+            .unwrap_or_else(ScaffoldEnv::development);
 
         let Some(current_env) = env_toml::Environment::get(workspace_root, &scaffold_env)? else {
             return Ok(());
@@ -193,15 +199,15 @@ impl Cmd {
             notify_watcher.watch(&canonicalize_path(package_path), RecursiveMode::Recursive)?;
         }
 
-        // Build a ProjectContext for pre/post-dev hooks. Both hooks receive the
-        // same context: per-contract wasm/deploy fields are not available at
-        // this level (extensions that need them should use compile/deploy/codegen
-        // hooks instead).
+        // Build a ProjectContext for pre/post-dev hooks. post-dev also gets what
+        // the latest build did with each contract; per-contract wasm paths and
+        // codegen output are not available at this level (extensions that need
+        // them should use compile/deploy/codegen hooks instead).
         let target_dir = metadata.target_directory.as_std_path();
         let watch_paths: Vec<PathBuf> = std::iter::once(workspace_root.to_path_buf())
             .chain(packages.iter().cloned())
             .collect();
-        let project_ctx = ProjectContext {
+        let mut project_ctx = ProjectContext {
             config: None,
             project_root: workspace_root.to_path_buf(),
             env: scaffold_env.to_string(),
@@ -223,6 +229,7 @@ impl Cmd {
                     contract_id: None,
                     ts_package_dir: None,
                     src_template_path: None,
+                    outcome: None,
                 })
                 .collect(),
             watch_paths,
@@ -232,9 +239,12 @@ impl Cmd {
         extension::run_hook(&extensions, HookName::PreDev, &project_ctx, &printer).await;
 
         let build_command = self.cloned_build_command(global_args);
-        if let Err(e) = build_command.0.run(&build_command.1).await {
-            printer.errorln(format!("Build error: {e}"));
-        }
+        Self::record_build(
+            build_command.0.run(&build_command.1).await,
+            &reports,
+            &printer,
+        )
+        .await;
         printer.infoln("Watching for changes. Press Ctrl+C to stop.");
 
         // Set up SIGTERM handler so graceful shutdown fires post-dev on both
@@ -265,7 +275,7 @@ impl Cmd {
                     let build_command_inner = build_command.clone();
                     if !*state {
                         *state = true;
-                        tokio::spawn(Self::debounced_rebuild(build_command_inner, Arc::clone(&rebuild_state_clone), printer_clone.clone()));
+                        tokio::spawn(Self::debounced_rebuild(build_command_inner, Arc::clone(&rebuild_state_clone), Arc::clone(&reports), printer_clone.clone()));
                     }
                 }
                 _ = tokio::signal::ctrl_c() => {
@@ -281,23 +291,57 @@ impl Cmd {
 
         // Fire post-dev after the loop — guaranteed to run for both Ctrl+C and
         // SIGTERM shutdowns.
+        for report in reports.lock().await.iter() {
+            if let Some(contract) = project_ctx
+                .contracts
+                .iter_mut()
+                .find(|c| c.name == report.name)
+            {
+                contract.contract_id.clone_from(&report.contract_id);
+                contract.wasm_hash.clone_from(&report.wasm_hash);
+                contract.outcome = Some(report.outcome.clone());
+            }
+        }
         extension::run_hook(&extensions, HookName::PostDev, &project_ctx, &printer).await;
 
         Ok(())
     }
 
+    /// Print a failed build, and keep what it did with each contract for `post-dev`
+    async fn record_build(
+        result: Result<Vec<ContractReport>, build::Error>,
+        reports: &Mutex<Vec<ContractReport>>,
+        printer: &Print,
+    ) {
+        let latest = match result {
+            Ok(latest) => latest,
+            Err(e) => {
+                printer.errorln(format!("Build error: {e}"));
+                match e {
+                    build::Error::BuildClients(clients::Error::ContractsFailed(latest)) => latest,
+                    _ => Vec::new(),
+                }
+            }
+        };
+        *reports.lock().await = latest;
+    }
+
     async fn debounced_rebuild(
         build_command: Arc<(build::Command, stellar_cli::commands::global::Args)>,
         rebuild_state: Arc<Mutex<bool>>,
+        reports: Arc<Mutex<Vec<ContractReport>>>,
         printer: Print,
     ) {
         // Debounce to avoid multiple rapid rebuilds
         time::sleep(std::time::Duration::from_secs(1)).await;
 
         printer.infoln("Changes detected. Rebuilding...");
-        if let Err(e) = build_command.0.run(&build_command.1).await {
-            printer.errorln(format!("Build error: {e}"));
-        }
+        Self::record_build(
+            build_command.0.run(&build_command.1).await,
+            &reports,
+            &printer,
+        )
+        .await;
         printer.infoln("Watching for changes. Press Ctrl+C to stop.");
 
         let mut state = rebuild_state.lock().await;
@@ -311,7 +355,7 @@ impl Cmd {
         self.build_cmd
             .build_clients_args
             .env
-            .get_or_insert(ScaffoldEnv::Development);
+            .get_or_insert_with(ScaffoldEnv::development);
         Arc::new((self.build_cmd.clone(), global_args.clone()))
     }
 }
@@ -0,0 +1,75 @@
+use stellar_scaffold_test::{AssertExt, TestEnv, rpc_url};
+
+fn environments_toml(assets: &str) -> String {
+    format!(
+        r#"
+[development]
+network = {{ rpc-url = "{}", network-passphrase = "Standalone Network ; February 2017" }}
+
+accounts = ["issuer", "alice"]
+[development.contracts]
+soroban_hello_world_contract.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+{assets}
+"#,
+        rpc_url(),
+    )
+}
+
+#[test]
+fn assets_are_only_created_once() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(environments_toml(
+            r#"
+[development.assets.usdc]
+code = "USDC"
+issuer = "issuer"
+balances = { alice = 100 }
+client = false
+"#,
+        ));
+
+        let stderr = env
+            .scaffold_build("development", true)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("Adding a trustline to \"usdc\" for \"alice\""));
+        assert!(stderr.contains("Paying 100 \"usdc\" to \"alice\""));
+        assert!(stderr.contains("Deploying the Stellar Asset Contract of \"usdc\""));
+
+        // Rebuilding finds everything in place and changes nothing
+        let stderr = env
+            .scaffold_build("development", true)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(!stderr.contains("Adding a trustline"));
+        assert!(!stderr.contains("Paying"));
+        assert!(!stderr.contains("Deploying the Stellar Asset Contract"));
+        assert!(stderr.contains("Asset \"usdc\" is ready"));
+    });
+}
+
+#[test]
+fn native_assets_take_no_balances() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(environments_toml(
+            r#"
+[development.assets.xlm]
+code = "native"
+balances = { alice = 100 }
+"#,
+        ));
+
+        let stderr = env
+            .scaffold_build("development", true)
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("Asset \"xlm\" is native XLM"));
+    });
+}
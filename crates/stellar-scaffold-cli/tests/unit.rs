@@ -12,6 +12,48 @@ fn no_environments_toml_ends_after_contract_build() {
     });
 }
 
+#[test]
+fn second_build_skips_unchanged_contracts() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.scaffold("build").assert().success();
+        let stderr = env.scaffold("build").assert().success().stderr_as_str();
+        assert!(
+            stderr.contains("Skipping soroban-hello-world-contract, unchanged since last build"),
+            "expected the contract to be skipped, got: {stderr}"
+        );
+    });
+}
+
+#[test]
+fn source_edit_rebuilds_only_that_contract() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.scaffold("build").assert().success();
+        let lib = env.cwd.join("contracts/hello_world/src/lib.rs");
+        let source = std::fs::read_to_string(&lib).unwrap();
+        std::fs::write(&lib, format!("{source}\n// edited\n")).unwrap();
+        let stderr = env.scaffold("build").assert().success().stderr_as_str();
+        assert!(
+            !stderr.contains("Skipping soroban-hello-world-contract"),
+            "expected the edited contract to be rebuilt, got: {stderr}"
+        );
+        assert!(
+            stderr.contains("Skipping soroban-increment-contract, unchanged since last build"),
+            "expected other contracts to be skipped, got: {stderr}"
+        );
+    });
+}
+
+#[test]
+fn snapshot_copies_wasm_to_versioned_name() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.scaffold("build").arg("--snapshot").assert().success();
+        let snapshot = env
+            .cwd
+            .join("target/stellar/local/soroban_hello_world_contract_0_0_0.wasm");
+        assert!(snapshot.exists(), "missing {}", snapshot.display());
+    });
+}
+
 #[test]
 fn uses_manifest_path_for_build_command() {
     TestEnv::from("soroban-init-boilerplate", |env| {
@@ -215,6 +215,10 @@ pub enum DeployKind {
     Upgraded,
     /// The contract already existed with the same WASM hash; no on-chain action was taken.
     Unchanged,
+    /// The contract already existed with another WASM and was kept as is, per its
+    /// upgrade strategy; no on-chain action was taken. `wasm_hash` is the hash of the
+    /// WASM it still runs.
+    Kept,
 }
 
 /// Context passed to `pre-deploy` and `post-deploy` hooks.
@@ -344,6 +348,55 @@ pub struct ProjectContractInfo {
 
     /// Thin client wrapper path. `None` if codegen was not run.
     pub src_template_path: Option<PathBuf>,
+
+    /// What the latest build did with the contract. `None` at `pre-dev`, and
+    /// for contracts the build skipped (e.g. `client = false`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<ContractOutcome>,
+}
+
+/// What a build with `--build-clients` did with a contract.
+///
+/// Available at `post-dev` via [`ProjectContractInfo::outcome`]. Serialized
+/// with a `status` tag, e.g. `{"status": "codegen_failed", "error": "…"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ContractOutcome {
+    /// The contract was instantiated for the first time and its client generated.
+    Deployed,
+    /// The contract's WASM was upgraded in-place and its client generated.
+    Upgraded,
+    /// The contract was already current; its client was generated if missing.
+    Unchanged,
+    /// The contract runs another WASM but was kept as is by its upgrade
+    /// strategy; its client was generated if missing.
+    Kept,
+    /// The contract has a pinned ID in `environments.toml`; only its client
+    /// was generated.
+    Pinned,
+    /// Uploading or deploying the contract failed; no client was generated.
+    DeployFailed { error: String },
+    /// The contract was deployed, but generating its client failed.
+    CodegenFailed { error: String },
+}
+
+impl ContractOutcome {
+    /// Whether the contract failed to deploy or to get a client.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::DeployFailed { .. } | Self::CodegenFailed { .. })
+    }
+}
+
+impl From<DeployKind> for ContractOutcome {
+    fn from(kind: DeployKind) -> Self {
+        match kind {
+            DeployKind::Fresh => Self::Deployed,
+            DeployKind::Upgraded => Self::Upgraded,
+            DeployKind::Unchanged => Self::Unchanged,
+            DeployKind::Kept => Self::Kept,
+        }
+    }
 }
 
 /// Context passed to `pre-dev` and `post-dev` hooks.
@@ -361,6 +414,7 @@ pub struct ProjectContractInfo {
 /// | `source_dirs`, `watch_paths` | ✓ | ✓ |
 /// | `network` | ✓ if `--build-clients` | ✓ if `--build-clients` |
 /// | `contracts[*].wasm_path` etc. | `None` | populated |
+/// | `contracts[*].outcome` | `None` | latest build's, if `--build-clients` |
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectContext {
     /// Per-extension config from `[env.ext.<name>]` in `environments.toml`.
@@ -401,3 +455,35 @@ pub struct ProjectContext {
     /// scaffold watch`.
     pub watch_paths: Vec<PathBuf>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contract_outcome_is_tagged_with_status() {
+        let failed = ContractOutcome::CodegenFailed {
+            error: "npm install failed".to_string(),
+        };
+        let json = serde_json::to_value(&failed).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "status": "codegen_failed", "error": "npm install failed" })
+        );
+        assert_eq!(
+            serde_json::from_value::<ContractOutcome>(json).unwrap(),
+            failed
+        );
+
+        assert_eq!(
+            serde_json::to_value(ContractOutcome::Kept).unwrap(),
+            serde_json::json!({ "status": "kept" })
+        );
+        assert_eq!(
+            serde_json::from_str::<ContractOutcome>(r#"{"status":"deployed"}"#).unwrap(),
+            ContractOutcome::Deployed
+        );
+        assert!(failed.is_failure());
+        assert!(!ContractOutcome::Pinned.is_failure());
+    }
+}
@@ -130,6 +130,15 @@ mod test {
         }
     }
 
+    #[test]
+    fn custom_network() {
+        let network = Network::from_any_passphrase("Private Testing Network ; October 2026");
+        let (custom, _) = generate_asset_id("native", &network).unwrap();
+        for network in &NETWORKS {
+            assert_ne!(custom, generate_asset_id("native", network).unwrap().0);
+        }
+    }
+
     #[test]
     fn native_client() {
         let lit: syn::LitStr = syn::parse_quote!("native");
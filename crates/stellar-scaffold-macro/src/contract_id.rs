@@ -0,0 +1,198 @@
+use std::{env, path::PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use stellar_build::{Network, cache::Cache, lock};
+use syn::{
+    Ident,
+    parse::{Parse, ParseStream, Result},
+};
+
+#[cfg(feature = "registry")]
+use crate::registry;
+use crate::{lockfile, manifest, parse_name_and_version, read_wasm, write_wasm};
+
+/// A named contract, resolved to its contract ID and the Wasm used to generate its client
+pub struct NamedContract {
+    pub name: String,
+    pub mod_name: Ident,
+    pub contract_id: String,
+    pub file: String,
+}
+
+impl Parse for NamedContract {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let (name, mod_name, version) = parse_name_and_version(input)?;
+        if version.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "named contracts have no version; use `import_contract_client!` to import a published Wasm",
+            ));
+        }
+        let (contract_id, file) = resolve(&name, &mod_name)?;
+        Ok(Self {
+            name,
+            mod_name,
+            contract_id,
+            file: file.display().to_string(),
+        })
+    }
+}
+
+/// Resolve a named contract on the network selected by `STELLAR_NETWORK`, from its pin in
+/// `stellar-registry.lock` if present and otherwise through the registry.
+fn resolve(name: &str, mod_name: &Ident) -> Result<(String, PathBuf)> {
+    let span = mod_name.span();
+    let target_dir = stellar_build::get_target_dir(&manifest()).unwrap();
+    let local_path = target_dir
+        .join("contracts")
+        .join(mod_name.to_string())
+        .with_extension("wasm");
+    let passphrase = Network::passphrase_from_env().passphrase().to_string();
+    let pinned =
+        lockfile(span)?.and_then(|lockfile| lockfile.get_contract(name, &passphrase).cloned());
+
+    // 1. Check local build target and the Wasm cache for the pinned Wasm
+    if let Some(entry) = &pinned {
+        if local_path.exists() && lock::sha256(&read_wasm(&local_path)) == entry.sha256 {
+            return Ok((entry.contract_id.clone(), local_path));
+        }
+        if let Some(wasm) = Cache::from_env().and_then(|cache| cache.get(&entry.sha256)) {
+            return Ok((entry.contract_id.clone(), write_wasm(&local_path, &wasm)));
+        }
+    }
+
+    // 2. If STELLAR_NO_REGISTRY set to 1, error
+    if let Ok(v) = env::var("STELLAR_NO_REGISTRY")
+        && &v == "1"
+    {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "`{name}` is not pinned in {} and STELLAR_NO_REGISTRY=1 so not checking Registry. \
+                Pin it with `stellar registry lock --contract {name}`",
+                lock::LOCKFILE
+            ),
+        ));
+    }
+
+    // 3. Look the contract up in the registry and download its Wasm
+    download(name, pinned, &passphrase, &local_path, span)
+}
+
+#[cfg(feature = "registry")]
+fn download(
+    name: &str,
+    pinned: Option<lock::ContractEntry>,
+    passphrase: &str,
+    local_path: &std::path::Path,
+    span: proc_macro2::Span,
+) -> Result<(String, PathBuf)> {
+    use stellar_registry_build::RegistryError;
+
+    let (entry, wasm) = registry::fetch_contract(name, pinned).map_err(|e| {
+        let network = lock::network_name(passphrase);
+        match e {
+            registry::Error::Registry(stellar_registry_build::Error::Contract(
+                RegistryError::NoSuchContractDeployed,
+            )) => syn::Error::new(
+                span,
+                format!(
+                    "No contract named `{name}` is registered on {network}. \
+                    Check the name and STELLAR_NETWORK (https://stellar.rgstry.xyz)"
+                ),
+            ),
+            e => syn::Error::new(
+                span,
+                format!("Could not resolve contract `{name}` on {network}: {e}"),
+            ),
+        }
+    })?;
+    Ok((entry.contract_id, write_wasm(local_path, &wasm)))
+}
+
+/// Without the `registry` feature only pinned contracts with cached Wasm can be imported
+#[cfg(not(feature = "registry"))]
+fn download(
+    name: &str,
+    _pinned: Option<lock::ContractEntry>,
+    _passphrase: &str,
+    _local_path: &std::path::Path,
+    span: proc_macro2::Span,
+) -> Result<(String, PathBuf)> {
+    Err(syn::Error::new(
+        span,
+        format!(
+            "The Wasm of `{name}` is not cached and the `registry` feature is disabled so not \
+            checking Registry. Pin it with `stellar registry lock --contract {name}` and build \
+            with the `registry` feature once to download it"
+        ),
+    ))
+}
+
+/// Generate a module with the contract's client, its contract ID and a client constructor
+pub fn generate(contract: &NamedContract) -> TokenStream {
+    let NamedContract {
+        name,
+        mod_name,
+        contract_id,
+        file,
+    } = contract;
+    let id_doc = format!(" Contract ID of `{name}` on the network this crate was built for");
+    let client_doc = format!(" Create a client for `{name}`");
+    quote! {
+        pub(crate) mod #mod_name {
+            #![allow(clippy::ref_option, clippy::too_many_arguments)]
+            use super::soroban_sdk;
+            soroban_sdk::contractimport!(file = #file);
+            #[doc = #id_doc]
+            pub fn contract_id(env: &soroban_sdk::Env) -> soroban_sdk::Address {
+                soroban_sdk::Address::from_str(&env, #contract_id)
+            }
+            #[doc = #client_doc]
+            pub fn client<'a>(env: &soroban_sdk::Env) -> Client<'a> {
+                Client::new(&env, &contract_id(env))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn oracle_client() {
+        let contract = NamedContract {
+            name: "unverified/oracle".to_string(),
+            mod_name: Ident::new("oracle", proc_macro2::Span::call_site()),
+            contract_id: "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC".to_string(),
+            file: "target/stellar/testnet/contracts/oracle.wasm".to_string(),
+        };
+        let expected = quote! {
+            pub(crate) mod oracle {
+                #![allow(clippy::ref_option, clippy::too_many_arguments)]
+                use super::soroban_sdk;
+                soroban_sdk::contractimport!(file = "target/stellar/testnet/contracts/oracle.wasm");
+                #[doc = " Contract ID of `unverified/oracle` on the network this crate was built for"]
+                pub fn contract_id(env: &soroban_sdk::Env) -> soroban_sdk::Address {
+                    soroban_sdk::Address::from_str(&env, "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC")
+                }
+                #[doc = " Create a client for `unverified/oracle`"]
+                pub fn client<'a>(env: &soroban_sdk::Env) -> Client<'a> {
+                    Client::new(&env, &contract_id(env))
+                }
+            }
+        };
+        assert_eq!(generate(&contract).to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn rejects_versions() {
+        let err = syn::parse2::<NamedContract>(quote!("unverified/oracle@1.0.0"))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("named contracts have no version"));
+    }
+}
@@ -4,15 +4,31 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 use std::env;
-use stellar_build::Network;
+use stellar_build::cache::Cache;
+use stellar_build::{Network, lock::Lockfile};
 use syn::parse::{Parse, ParseStream, Result};
 use syn::{Ident, LitStr, parse_macro_input};
 
 mod asset;
+mod contract_id;
+#[cfg(feature = "registry")]
+mod registry;
 
-pub(crate) fn manifest() -> std::path::PathBuf {
+pub(crate) fn manifest_dir() -> std::path::PathBuf {
     std::path::PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("failed to find cargo manifest"))
-        .join("Cargo.toml")
+}
+
+pub(crate) fn manifest() -> std::path::PathBuf {
+    manifest_dir().join("Cargo.toml")
+}
+
+/// Pins from `stellar-registry.lock` in the crate's directory or one of its parents up to the
+/// workspace root, or `None` without a lockfile. Only `stellar registry lock` writes the
+/// lockfile; the macros just read it.
+pub(crate) fn lockfile(span: Span) -> Result<Option<Lockfile>> {
+    Lockfile::find(&manifest_dir())
+        .map(|path| Lockfile::load(&path).map_err(|e| lock_error(span, &e)))
+        .transpose()
 }
 
 /// Generates a contract Client for a given contract.
@@ -130,20 +146,44 @@ fn resolve_wasm_path(
     mod_name: &Ident,
     version: Option<&str>,
 ) -> Result<std::path::PathBuf> {
+    let span = mod_name.span();
     let target_dir = stellar_build::get_target_dir(&manifest()).unwrap();
     let local_path = build_local_wasm_path(&target_dir, mod_name, version);
-
-    // 1. Check local build target
-    if local_path.exists() {
+    let passphrase = Network::passphrase_from_env().passphrase().to_string();
+    let pinned = pin(
+        lockfile(span)?.as_ref(),
+        lookup_name,
+        version,
+        &passphrase,
+        local_path.exists(),
+    )
+    .map_err(|e| syn::Error::new(span, e))?;
+
+    // 1. Check local build target. A pinned Wasm must match its hash; otherwise it is
+    // left over from a previous pin and the pinned version is fetched again below.
+    if local_path.exists()
+        && pinned
+            .as_ref()
+            .is_none_or(|entry| entry.verify(&read_wasm(&local_path)).is_ok())
+    {
         return Ok(local_path.canonicalize().expect("canonicalize failed"));
     }
 
-    // 2. If STELLAR_NO_REGISTRY set to 1, error
+    // 2. Check the Wasm cache for the pinned hash
+    if let Some(wasm) = pinned
+        .as_ref()
+        .zip(Cache::from_env())
+        .and_then(|(entry, cache)| cache.get(&entry.sha256))
+    {
+        return Ok(write_wasm(&local_path, &wasm));
+    }
+
+    // 3. If STELLAR_NO_REGISTRY set to 1, error
     if let Ok(v) = env::var("STELLAR_NO_REGISTRY")
         && &v == "1"
     {
         return Err(syn::Error::new(
-            mod_name.span(),
+            span,
             format!(
                 "No local wasm found and STELLAR_NO_REGISTRY=1 so not checking Registry. \
                 Download manually with `stellar registry download {lookup_name}`"
@@ -151,61 +191,137 @@ fn resolve_wasm_path(
         ));
     }
 
-    // 3. if var absent or set to something else, try to download
-    download_from_registry(lookup_name, &local_path, mod_name.span(), version)
+    // 4. if var absent or set to something else, download it
+    download_wasm(lookup_name, version, pinned, &local_path, span)
+}
+
+/// Pin of `lookup_name` from the lockfile, if there is one. With a lockfile, the pin must be
+/// of the imported version, and every Wasm that isn't built locally must be pinned.
+fn pin(
+    lockfile: Option<&Lockfile>,
+    lookup_name: &str,
+    version: Option<&str>,
+    passphrase: &str,
+    built_locally: bool,
+) -> std::result::Result<Option<stellar_build::lock::Entry>, String> {
+    let Some(lockfile) = lockfile else {
+        return Ok(None);
+    };
+    let name = match version {
+        Some(version) => format!("{lookup_name}@{version}"),
+        None => lookup_name.to_string(),
+    };
+    match lockfile.get(lookup_name, passphrase) {
+        Some(entry) if version.is_some_and(|v| v != entry.version) => Err(format!(
+            "`{name}` is imported but {} pins version {} on {}. \
+            Run `stellar registry lock --update {name}` to pin it",
+            stellar_build::lock::LOCKFILE,
+            entry.version,
+            entry.network,
+        )),
+        Some(entry) => Ok(Some(entry.clone())),
+        None if built_locally => Ok(None),
+        None => Err(format!(
+            "`{name}` is not pinned in {} for {}. \
+            Run `stellar registry lock --update {name}` to pin it",
+            stellar_build::lock::LOCKFILE,
+            stellar_build::lock::network_name(passphrase),
+        )),
+    }
 }
 
-fn download_from_registry(
+#[cfg(feature = "registry")]
+fn download_wasm(
     lookup_name: &str,
+    version: Option<&str>,
+    pinned: Option<stellar_build::lock::Entry>,
     local_path: &std::path::Path,
     span: Span,
-    version: Option<&str>,
 ) -> Result<std::path::PathBuf> {
-    // 1. create `target/stellar/[network]` directory, if not already present
+    let wasm = registry::fetch(lookup_name, version, pinned)
+        .map_err(|e| not_found(lookup_name, local_path, span, &e))?;
+    Ok(write_wasm(local_path, &wasm))
+}
+
+/// Without the `registry` feature only pinned and cached Wasm can be imported
+#[cfg(not(feature = "registry"))]
+fn download_wasm(
+    lookup_name: &str,
+    _version: Option<&str>,
+    _pinned: Option<stellar_build::lock::Entry>,
+    _local_path: &std::path::Path,
+    span: Span,
+) -> Result<std::path::PathBuf> {
+    Err(syn::Error::new(
+        span,
+        format!(
+            "No local wasm found and the `registry` feature is disabled so not checking Registry. \
+            Pin it with `stellar registry lock {lookup_name}` and download it with \
+            `stellar registry download {lookup_name}`"
+        ),
+    ))
+}
+
+fn read_wasm(path: &std::path::Path) -> Vec<u8> {
+    std::fs::read(path).expect("reading wasm failed")
+}
+
+/// Write a Wasm to the `target/stellar/[network]` directory, returning its canonical path
+fn write_wasm(local_path: &std::path::Path, wasm: &[u8]) -> std::path::PathBuf {
     let parent = local_path.parent().expect("no parent");
-    if !parent.exists() {
-        std::fs::create_dir_all(parent).expect("creating parent directory failed");
-    }
+    std::fs::create_dir_all(parent).expect("creating parent directory failed");
+    std::fs::write(local_path, wasm).expect("writing wasm failed");
+    local_path.canonicalize().expect("canonicalize failed")
+}
 
-    // 2. download using `stellar registry download`
-    let mut args = vec![
-        "registry".to_string(),
-        "download".to_string(),
-        lookup_name.to_string(),
-        "--out-file".to_string(),
-        local_path.display().to_string(),
-    ];
-    if let Some(v) = version {
-        args.push("--version".to_string());
-        args.push(v.to_string());
-    }
-    let status = std::process::Command::new("stellar")
-        .args(&args)
-        .status()
-        .expect(
-            "failed to execute `stellar registry download`; try `cargo install stellar-registry-cli` and try again",
-        );
+fn lock_error(span: Span, error: &stellar_build::lock::Error) -> syn::Error {
+    syn::Error::new(span, error.to_string())
+}
 
-    // 3. check status
-    if status.success() && local_path.exists() {
-        Ok(local_path.canonicalize().expect("canonicalize failed"))
-    } else {
-        let local_path = local_path.display().to_string();
-        Err(syn::Error::new(
-            span,
-            format!(
-                "Could not find Wasm `{lookup_name}`. Checked: \
-                \n\n• {local_path} \
-                \n• `stellar registry download {lookup_name}` \
-                \n\nYou can: \
-                \n\n1. check the name & network and try again (https://stellar.rgstry.xyz) \
-                \n2. add this Wasm to your local `target` directory manually \
-                (perhaps by compiling a contract) \
-                \n3. run `stellar registry download {lookup_name}` yourself. \
-                \n\nSet STELLAR_NO_REGISTRY=1 to skip registry lookup."
-            ),
-        ))
-    }
+#[cfg(feature = "registry")]
+fn not_found(
+    lookup_name: &str,
+    local_path: &std::path::Path,
+    span: Span,
+    error: &registry::Error,
+) -> syn::Error {
+    let local_path = local_path.display().to_string();
+    syn::Error::new(
+        span,
+        format!(
+            "Could not find Wasm `{lookup_name}`: {error}. Checked: \
+            \n\n• {local_path} \
+            \n• the registry \
+            \n\nYou can: \
+            \n\n1. check the name & network and try again (https://stellar.rgstry.xyz) \
+            \n2. add this Wasm to your local `target` directory manually \
+            (perhaps by compiling a contract) \
+            \n3. run `stellar registry download {lookup_name}` yourself. \
+            \n\nSet STELLAR_NO_REGISTRY=1 to skip registry lookup."
+        ),
+    )
+}
+
+/// Generates a client and contract ID for a contract registered by name in the registry.
+/// The name is resolved on the network selected by `STELLAR_NETWORK` when the crate is built
+/// unless it is pinned in `stellar-registry.lock` by `stellar registry lock`.
+///
+/// # Usage
+///
+/// ```ignore
+/// import_contract_id!("unverified/oracle");
+///
+/// let price = oracle::client(&env).lastprice(&asset);
+/// let address = oracle::contract_id(&env);
+/// ```
+///
+/// # Panics
+///
+/// This function may panic if the target directory cannot be found or written to.
+#[proc_macro]
+pub fn import_contract_id(input: TokenStream) -> TokenStream {
+    let contract = parse_macro_input!(input as contract_id::NamedContract);
+    contract_id::generate(&contract).into()
 }
 
 /// Generates a contract Client for a given asset.
@@ -393,3 +509,68 @@ mod test_build_local_wasm_path {
         assert_eq!(path, Path::new("target/foo_1_0_0-rc_1.wasm"));
     }
 }
+
+#[cfg(test)]
+mod test_pin {
+    use super::*;
+    use stellar_build::lock::Entry;
+
+    const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+    fn lockfile() -> Lockfile {
+        let mut lockfile = Lockfile::default();
+        lockfile.insert(Entry {
+            name: "unverified/oracle".to_string(),
+            network: stellar_build::lock::network_name(PASSPHRASE),
+            registry: "CAAX".to_string(),
+            version: "1.2.0".to_string(),
+            sha256: "5d1c".to_string(),
+        });
+        lockfile
+    }
+
+    #[test]
+    fn no_lockfile() {
+        assert_eq!(pin(None, "oracle", None, PASSPHRASE, false), Ok(None));
+    }
+
+    #[test]
+    fn pinned_version() {
+        let lockfile = lockfile();
+        for version in [None, Some("1.2.0")] {
+            let entry = pin(
+                Some(&lockfile),
+                "unverified/oracle",
+                version,
+                PASSPHRASE,
+                false,
+            );
+            assert_eq!(entry.unwrap().unwrap().version, "1.2.0");
+        }
+    }
+
+    #[test]
+    fn version_mismatch() {
+        let error = pin(
+            Some(&lockfile()),
+            "unverified/oracle",
+            Some("1.3.0"),
+            PASSPHRASE,
+            true,
+        )
+        .unwrap_err();
+        assert!(error.contains("pins version 1.2.0"));
+        assert!(error.contains("stellar registry lock --update unverified/oracle@1.3.0"));
+    }
+
+    #[test]
+    fn unpinned_import() {
+        let lockfile = lockfile();
+        let error = pin(Some(&lockfile), "other", None, PASSPHRASE, false).unwrap_err();
+        assert!(error.contains("stellar registry lock --update other"));
+        assert_eq!(
+            pin(Some(&lockfile), "other", None, PASSPHRASE, true),
+            Ok(None)
+        );
+    }
+}
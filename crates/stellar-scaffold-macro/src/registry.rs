@@ -0,0 +1,99 @@
+use std::{env, future::Future, time::Duration};
+
+use stellar_build::cache::Cache;
+use stellar_build::lock::{ContractEntry, Entry};
+use stellar_cli::config::{self, locator, network};
+use stellar_registry_build::wasm;
+
+/// Env var with the number of seconds allowed for resolving and downloading a Wasm
+pub(crate) const TIMEOUT_ENV: &str = "STELLAR_REGISTRY_TIMEOUT";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    Registry(#[from] stellar_registry_build::Error),
+    #[error("failed to start async runtime: {0}")]
+    Runtime(std::io::Error),
+    #[error("timed out after {}s; set {TIMEOUT_ENV} to allow longer", .0.as_secs())]
+    Timeout(Duration),
+}
+
+/// Resolve and download a Wasm from the registry. A pinned Wasm is fetched at its
+/// pinned version; otherwise `version`, or the latest version, is resolved.
+///
+/// Downloads are verified against the pinned hash and stored in the Wasm cache.
+pub(crate) fn fetch(
+    lookup_name: &str,
+    version: Option<&str>,
+    pinned: Option<Entry>,
+) -> Result<Vec<u8>, Error> {
+    let config = config_from_env();
+    let cache = Cache::from_env();
+    block_on(async {
+        let entry = match pinned {
+            Some(entry) => entry,
+            None => wasm::resolve(&config, lookup_name, version).await?,
+        };
+        wasm::fetch(&config, &entry.sha256, cache.as_ref()).await
+    })
+}
+
+/// Resolve a named contract through the registry, unless pinned, and download its Wasm
+pub(crate) fn fetch_contract(
+    lookup_name: &str,
+    pinned: Option<ContractEntry>,
+) -> Result<(ContractEntry, Vec<u8>), Error> {
+    let config = config_from_env();
+    let cache = Cache::from_env();
+    block_on(async {
+        let entry = match pinned {
+            Some(entry) => entry,
+            None => wasm::resolve_contract(&config, lookup_name).await?,
+        };
+        let wasm = wasm::fetch(&config, &entry.sha256, cache.as_ref()).await?;
+        Ok((entry, wasm))
+    })
+}
+
+/// Run a registry lookup to completion within the timeout
+fn block_on<T>(
+    future: impl Future<Output = Result<T, stellar_registry_build::Error>>,
+) -> Result<T, Error> {
+    let timeout = timeout();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(Error::Runtime)?;
+    Ok(runtime
+        .block_on(tokio::time::timeout(timeout, future))
+        .map_err(|_| Error::Timeout(timeout))??)
+}
+
+fn timeout() -> Duration {
+    env::var(TIMEOUT_ENV)
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .map_or(DEFAULT_TIMEOUT, Duration::from_secs)
+}
+
+/// Network configuration from the same env vars the `stellar` CLI reads,
+/// defaulting to the `local` network like `target/stellar/[network]`
+fn config_from_env() -> config::Args {
+    let var = |name| env::var(name).ok();
+    let rpc_url = var("STELLAR_RPC_URL");
+    config::Args {
+        network: network::Args {
+            network: var("STELLAR_NETWORK").or_else(|| rpc_url.is_none().then(|| "local".into())),
+            rpc_url,
+            network_passphrase: var("STELLAR_NETWORK_PASSPHRASE"),
+            ..Default::default()
+        },
+        locator: locator::Args {
+            config_dir: var("STELLAR_CONFIG_DIR").map(Into::into),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
@@ -27,8 +27,8 @@
 use crate::report::Reporter;
 use clap::{Parser, Subcommand};
 use stellar_scaffold_ext_types::{
-    CodegenContext, CompileContext, DeployContext, DeployKind, ExtensionManifest, HookName,
-    ProjectContext,
+    CodegenContext, CompileContext, ContractOutcome, DeployContext, DeployKind, ExtensionManifest,
+    HookName, ProjectContext,
 };
 pub mod report;
 pub mod state;
@@ -273,6 +273,7 @@ fn cmd_post_deploy() {
         let kind = match ctx.deploy_kind {
             Some(DeployKind::Upgraded) => "upgraded in-place",
             Some(DeployKind::Unchanged) => "unchanged",
+            Some(DeployKind::Kept) => "kept at its current Wasm",
             Some(DeployKind::Fresh) | None => "deployed fresh",
             // DeployKind is #[non_exhaustive]; future variants default to this.
             Some(_) => "deployed",
@@ -374,8 +375,10 @@ fn cmd_post_dev() {
     if let Some(start) = state.dev_start.take() {
         let elapsed = state::elapsed_since(start);
 
-        let (succeeded, failed): (Vec<_>, Vec<_>) =
-            ctx.contracts.iter().partition(|c| c.wasm_path.is_some());
+        let (failed, succeeded): (Vec<_>, Vec<_>) = ctx
+            .contracts
+            .iter()
+            .partition(|c| c.outcome.as_ref().is_some_and(ContractOutcome::is_failure));
 
         let summary = if failed.is_empty() {
             format!(